@@ -0,0 +1,8 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+compile_error!("either the `std` or the `alloc` feature must be enabled");
+
+pub mod lru;
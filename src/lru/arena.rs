@@ -1,8 +1,12 @@
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt;
+use core::marker::PhantomData;
+
 use crate::lru::err::ArenaOOM;
 
 // 内存单位的索引信息
 // 用于在内存区域中查询数据
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Index {
     // 该内存单位在整个内存区域的下标
     // 这里的下标对应 Vec 中的下标
@@ -13,20 +17,34 @@ pub struct Index {
     generation: u64,
 }
 
+// 只读访问，用于日志和调试；字段本身保持私有，只能由 Arena 创建 Index
+impl Index {
+    pub fn idx(&self) -> usize {
+        self.idx
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
 // 最小的一块内存单位，存放对应的值（V）
 // 有两种状态：空闲 / 被占用
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Entry<T> {
     Free {
         // 下一块空闲区域的下标
         next_free: Option<usize>,
+        // 下一次占用该内存单位时使用的代数
+        // 每次释放时加 1，因此大于该内存单位曾经使用过的所有代数
+        generation: u64,
     },
     Occupied {
         // 当前内存中的值
         value: T,
         // 当前内存中数据的代数
-        // 每次插入时都会更新代数
-        // 因此每个数据都有唯一的代数
+        // 代数只在同一个内存单位内递增，不同内存单位的代数可能相同
+        // 因此 (下标, 代数) 才能唯一确定一个数据
         // 查询时将 Index 的代数和 Entry 中实际的代数进行比较
         // 如果不匹配说明 Index 过期
         generation: u64,
@@ -34,6 +52,7 @@ pub enum Entry<T> {
 }
 
 // 整个连续的内存区域
+#[derive(Clone)]
 pub struct Arena<T> {
     // 该连续的内存区域中的所有内存单位
     // 通过 Vec 存储，因为 Vec 本身就是一段连续的内存空间
@@ -41,12 +60,41 @@ pub struct Arena<T> {
     // 该连续的内存区域的容量，表明可以容纳 cap 个内存单位
     cap: usize,
 
-    // 当前整个内存区域下一次插入数据时的代数
+    // 所有内存单位使用过的最大代数 + 1
+    // 新扩充的内存单位从该代数开始，避免与旧的 Index 重复
     generation: u64,
 
     // 首个空闲区域的下标（逻辑上）
     // 可能在 Vec 上还有值，但会被覆盖
     free_list_head: Option<usize>,
+
+    // 扩容时是否将新区域接在空闲链表尾部
+    // 为 true 时空闲区域按下标升序分配，顺序插入的数据在 Vec 中连续
+    ordered: bool,
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 只输出被占用的内存单位，格式为 Index -> 值
+impl<T: fmt::Debug> fmt::Debug for Arena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Arena")
+            .field("cap", &self.cap)
+            .field("occupied", &Occupied(self))
+            .finish()
+    }
+}
+
+struct Occupied<'a, T>(&'a Arena<T>);
+
+impl<T: fmt::Debug> fmt::Debug for Occupied<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.0.iter()).finish()
+    }
 }
 
 impl<T> Arena<T> {
@@ -56,6 +104,7 @@ impl<T> Arena<T> {
             cap: 0,
             generation: 0,
             free_list_head: None,
+            ordered: false,
         }
     }
 
@@ -66,10 +115,26 @@ impl<T> Arena<T> {
         arena
     }
 
+    // 与 new_with_cap 相同，但之后的扩容不会把新区域插到空闲链表头部
+    // 这样顺序插入的数据会按下标升序占用内存单位，遍历时对缓存更友好
+    // 代价是扩容时需要遍历空闲链表找到尾部，且 remove 释放的区域
+    // 仍然通过头插法（LIFO）优先被复用
+    pub fn new_with_cap_ordered(cap: usize) -> Self {
+        let mut arena = Self::new();
+        arena.ordered = true;
+        arena.reserve(cap);
+        arena
+    }
+
     // 在内存区域尾部扩充 usize 个连续的内存单位
     // 如果内存区域尾部本身就可以容纳额外的 usize 个连续的内存单位
     // 那么就不会进行扩充
     pub fn reserve(&mut self, cap: usize) {
+        // 不扩充时直接返回，否则空闲链表头会指向不存在的区域
+        if cap == 0 {
+            return;
+        }
+
         // 使用 Vec 的扩充函数
         // 如果 Vec.capacity() >= Vec.size() + cap
         // 那么维持 Vec.capacity 不变
@@ -84,14 +149,23 @@ impl<T> Arena<T> {
         // 记录原先的首个空闲区域
         // 用于让新空闲区域尾部的 next_free 指向它
         let old_free = self.free_list_head;
+        if self.ordered && old_free.is_some() {
+            self.append_free_region(start, end);
+            self.cap += cap;
+            self.assert_integrity();
+            return;
+        }
+        let generation = self.generation;
         self.items.extend((start..end).map(|i| {
             if i == end - 1 {
                 Entry::Free {
                     next_free: old_free,
+                    generation,
                 }
             } else {
                 Entry::Free {
                     next_free: Some(i + 1),
+                    generation,
                 }
             }
         }));
@@ -103,8 +177,43 @@ impl<T> Arena<T> {
         self.free_list_head = Some(start);
         // 更新内存容量
         self.cap += cap;
+        self.assert_integrity();
+    }
+
+    // 与 reserve 相同，但内存申请失败时返回 ArenaOOM 而不是终止进程
+    // 申请失败时 Arena 保持不变
+    pub fn try_reserve(&mut self, cap: usize) -> Result<(), ArenaOOM> {
+        self.items.try_reserve_exact(cap).map_err(|_| ArenaOOM {})?;
+        // 内存已经申请成功，reserve 不会再次申请
+        self.reserve(cap);
+        Ok(())
+    }
+
+    // 将 [start, end) 的新区域接在空闲链表的尾部
+    fn append_free_region(&mut self, start: usize, end: usize) {
+        let mut last = self.free_list_head;
+        while let Some(i) = last {
+            match self.items[i] {
+                Entry::Free { next_free: Some(next), .. } => last = Some(next),
+                _ => break,
+            }
+        }
+
+        let generation = self.generation;
+        self.items.extend((start..end).map(|i| Entry::Free {
+            next_free: if i == end - 1 { None } else { Some(i + 1) },
+            generation,
+        }));
+        if let Some(i) = last {
+            if let Entry::Free { next_free, .. } = &mut self.items[i] {
+                *next_free = Some(start);
+            }
+        }
     }
 
+    // 首个空闲区域的代数耗尽（已经使用到 u64::MAX - 1）时同样返回 ArenaOOM
+    // 代数不回绕，保证过期的 Index 永远不会与新数据的代数相同
+    #[must_use = "the returned Index is the only way to access the value"]
     pub fn insert(&mut self, value: T) -> Result<Index, ArenaOOM> {
         // 如果 Arena 还没有初始化，返回错误
         if self.free_list_head.is_none() {
@@ -113,26 +222,26 @@ impl<T> Arena<T> {
 
         // 检查首个空闲区域是否空闲
         // 如果不空闲说明内存已满，返回错误
-        // 否则先记录该区域的下标（用于占用）并先将首个空闲区域指向下一个空闲区域
+        // 否则先记录该区域的下标和代数（用于占用）并先将首个空闲区域指向下一个空闲区域
         let old_free = self.free_list_head;
-        if let Entry::Free { next_free } = self.items[old_free.unwrap()] {
+        let generation = if let Entry::Free { next_free, generation } = self.items[old_free.unwrap()] {
+            let next_generation = generation.checked_add(1).ok_or(ArenaOOM {})?;
+            self.generation = self.generation.max(next_generation);
             self.free_list_head = next_free;
+            generation
         } else {
             return Err(ArenaOOM {});
-        }
+        };
 
         // 占用之前记录的空闲区域
-        let entry = Entry::Occupied {
-            value: value,
-            generation: self.generation,
-        };
+        let entry = Entry::Occupied { value, generation };
         self.items[old_free.unwrap()] = entry;
-        self.generation += 1;
+        self.assert_integrity();
 
         // 返回该被占用区域的索引信息
         Ok(Index {
             idx: old_free.unwrap(),
-            generation: self.generation - 1,
+            generation,
         })
     }
 
@@ -149,13 +258,15 @@ impl<T> Arena<T> {
                 return None;
             }
 
-            // 释放当前被占用的存储区域
+            // 释放当前被占用的存储区域，下一次占用时代数加 1
             // 并通过头插法更新首个空闲区域下标
             let entry = Entry::<T>::Free {
                 next_free: self.free_list_head,
+                generation: generation.saturating_add(1),
             };
             let old_entry = core::mem::replace(&mut self.items[index.idx], entry);
             self.free_list_head = Some(index.idx);
+            self.assert_integrity();
 
             // 将被释放的数据所有权返回
             if let Entry::Occupied {
@@ -170,10 +281,234 @@ impl<T> Arena<T> {
         None
     }
 
+    // 批量释放 indices 对应的内存单位，按顺序返回被释放数据的所有权
+    // 过期（代数不匹配）或重复的 Index 对应 None
+    // 被释放的区域按照 indices 的顺序串联后，一次性接到空闲链表头部
+    pub fn remove_many(&mut self, indices: &[Index]) -> Vec<Option<T>> {
+        let mut freed = Vec::with_capacity(indices.len());
+        let values = indices
+            .iter()
+            .map(|index| {
+                match self.items.get(index.idx) {
+                    Some(Entry::Occupied { generation, .. })
+                        if *generation == index.generation => {}
+                    _ => return None,
+                }
+
+                let old_entry = core::mem::replace(
+                    &mut self.items[index.idx],
+                    Entry::Free {
+                        next_free: None,
+                        generation: index.generation.saturating_add(1),
+                    },
+                );
+                freed.push(index.idx);
+                match old_entry {
+                    Entry::Occupied { value, .. } => Some(value),
+                    Entry::Free { .. } => None,
+                }
+            })
+            .collect();
+
+        // 串联被释放的区域：freed[0] -> freed[1] -> ... -> 原先的首个空闲区域
+        let mut next_free = self.free_list_head;
+        for &idx in freed.iter().rev() {
+            if let Entry::Free { next_free: slot, .. } = &mut self.items[idx] {
+                *slot = next_free;
+            }
+            next_free = Some(idx);
+        }
+        self.free_list_head = next_free;
+        self.assert_integrity();
+
+        values
+    }
+
     pub fn cap(&self) -> usize {
         self.cap
     }
 
+    // insert / remove / reserve 之后检查空闲链表的完整性
+    // 检查需要遍历所有内存单位，因此只在单元测试中进行，其它构建中为空操作
+    #[inline]
+    fn assert_integrity(&self) {
+        #[cfg(test)]
+        assert_eq!(self.check_integrity(), Ok(()));
+    }
+
+    // 检查空闲链表的完整性：没有环、没有重复的内存单位、链表中的内存单位都是空闲的，
+    // 且空闲的个数与被占用的个数之和等于容量
+    pub fn check_integrity(&self) -> Result<(), String> {
+        if self.items.len() != self.cap {
+            return Err(format!(
+                "cap {} does not match {} slots",
+                self.cap,
+                self.items.len()
+            ));
+        }
+
+        let mut visited = vec![false; self.items.len()];
+        let mut free = 0;
+        let mut current = self.free_list_head;
+        while let Some(i) = current {
+            match self.items.get(i) {
+                Some(Entry::Free { next_free, .. }) => {
+                    if visited[i] {
+                        return Err(format!("free list visits slot {} twice", i));
+                    }
+                    visited[i] = true;
+                    free += 1;
+                    current = *next_free;
+                }
+                Some(Entry::Occupied { .. }) => {
+                    return Err(format!("free list points to occupied slot {}", i));
+                }
+                None => return Err(format!("free list points to slot {} out of range", i)),
+            }
+        }
+
+        let occupied = self.occupied_len();
+        if free + occupied != self.cap {
+            return Err(format!(
+                "{} free and {} occupied slots do not add up to cap {}",
+                free, occupied, self.cap
+            ));
+        }
+        Ok(())
+    }
+
+    // 沿着空闲链表统计空闲的内存单位个数
+    pub fn free_len(&self) -> usize {
+        let mut len = 0;
+        let mut current = self.free_list_head;
+        while let Some(i) = current {
+            match self.items.get(i) {
+                Some(Entry::Free { next_free, .. }) => {
+                    len += 1;
+                    current = *next_free;
+                }
+                _ => break,
+            }
+        }
+        // 每个内存单位要么被占用，要么位于空闲链表中
+        debug_assert_eq!(len + self.occupied_len(), self.cap);
+        len
+    }
+
+    // 被占用的内存单位个数
+    pub fn occupied_len(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|entry| matches!(entry, Entry::Occupied { .. }))
+            .count()
+    }
+
+    // 空闲链表的碎片程度，取值范围 [0, 1]
+    // 统计空闲链表中相邻两个区域的下标不连续（不是 i -> i + 1）的比例
+    // 刚扩容的空闲区域是连续的，因此为 0；反复插入删除后会逐渐升高
+    pub fn fragmentation(&self) -> f32 {
+        let mut links = 0;
+        let mut breaks = 0;
+        let mut current = self.free_list_head;
+        while let Some(i) = current {
+            match self.items.get(i) {
+                Some(Entry::Free { next_free, .. }) => {
+                    if let Some(next) = next_free {
+                        links += 1;
+                        if *next != i + 1 {
+                            breaks += 1;
+                        }
+                    }
+                    current = *next_free;
+                }
+                _ => break,
+            }
+        }
+
+        if links == 0 {
+            return 0.0;
+        }
+        breaks as f32 / links as f32
+    }
+
+    // 将所有被占用的内存单位按原有顺序移动到内存区域的头部，并丢弃所有空闲区域
+    // 容量缩小为被占用的内存单位个数，数据的代数保持不变
+    // 被移动的数据的 Index 会失效！按照原下标升序返回所有数据的 (旧 Index, 新 Index)
+    // 注意：底层 Vec 申请的内存不会被释放
+    pub fn compact(&mut self) -> Vec<(Index, Index)> {
+        let mut remap = Vec::new();
+        let mut write = 0;
+        // 被移动的数据使用一个新的代数，避免新位置上旧数据的过期 Index 重新生效
+        let fresh = self.generation;
+        let mut moved = false;
+        for read in 0..self.items.len() {
+            if let Entry::Occupied { generation, .. } = &mut self.items[read] {
+                let old = Index { idx: read, generation: *generation };
+                if read != write {
+                    *generation = fresh;
+                    moved = true;
+                }
+                let new = Index { idx: write, generation: *generation };
+                self.items.swap(read, write);
+                remap.push((old, new));
+                write += 1;
+            }
+        }
+        if moved {
+            self.generation = fresh.saturating_add(1);
+        }
+        self.items.truncate(write);
+        self.cap = write;
+        self.free_list_head = None;
+        remap
+    }
+
+    // 与 compact 相同，并在整理后释放底层 Vec 多余的内存
+    pub fn shrink_to_fit(&mut self) -> Vec<(Index, Index)> {
+        let remap = self.compact();
+        self.items.shrink_to_fit();
+        remap
+    }
+
+    // 使新的 Arena 从 generation 开始分配代数
+    // 用于重建 Arena 时避免旧的 Index 与新数据的代数重复
+    pub(crate) fn with_generation(mut self, generation: u64) -> Self {
+        self.raise_generation(generation);
+        self
+    }
+
+    // 将空闲区域以及之后新扩充区域的代数提高到至少 generation
+    fn raise_generation(&mut self, generation: u64) {
+        self.generation = self.generation.max(generation);
+        for entry in self.items.iter_mut() {
+            if let Entry::Free { generation: slot, .. } = entry {
+                *slot = (*slot).max(generation);
+            }
+        }
+    }
+
+    // 目前所有内存单位使用过的最大代数 + 1
+    pub fn max_generation(&self) -> u64 {
+        self.generation
+    }
+
+    // 按照下标升序遍历所有被占用的内存单位，返回其 Index 和值
+    pub fn iter(&self) -> impl Iterator<Item = (Index, &T)> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, entry)| match entry {
+                Entry::Occupied { value, generation } => Some((
+                    Index {
+                        idx,
+                        generation: *generation,
+                    },
+                    value,
+                )),
+                Entry::Free { .. } => None,
+            })
+    }
+
     pub fn get(&self, index: &Index) -> Option<&T> {
         if let Some(Entry::Occupied { value, generation }) = self.items.get(index.idx) {
             if &index.generation == generation {
@@ -193,6 +528,58 @@ impl<T> Arena<T> {
 
         None
     }
+
+    // 返回独占整个 Arena 的裸指针视图，可以同时持有多个不同内存单位的可变引用
+    pub(crate) fn raw_mut(&mut self) -> RawArena<'_, T> {
+        RawArena {
+            items: self.items.as_mut_ptr(),
+            len: self.items.len(),
+            marker: PhantomData,
+        }
+    }
+}
+
+// 由 Arena::raw_mut 创建，生命周期内独占借用整个 Arena
+pub(crate) struct RawArena<'a, T> {
+    items: *mut Entry<T>,
+    len: usize,
+    marker: PhantomData<&'a mut Arena<T>>,
+}
+
+impl<'a, T> RawArena<'a, T> {
+    // 与 Arena::get_mut 相同，但返回的引用与 RawArena 的生命周期相同
+    // Safety：调用方需要保证同一个 index 在 'a 内最多获取一次，否则会产生重叠的可变引用
+    pub(crate) unsafe fn get_mut(&mut self, index: &Index) -> Option<&'a mut T> {
+        if index.idx >= self.len {
+            return None;
+        }
+        // SAFETY: idx 没有越界，items 在 'a 内被独占借用，且调用方保证不会重复获取同一个内存单位
+        match unsafe { &mut *self.items.add(index.idx) } {
+            Entry::Occupied { value, generation } if *generation == index.generation => Some(value),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+impl<T> Arena<T> {
+    // 仅用于测试：将所有空闲区域以及之后新扩充区域的代数设置为 generation
+    pub(crate) fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+        for entry in self.items.iter_mut() {
+            if let Entry::Free { generation: slot, .. } = entry {
+                *slot = generation;
+            }
+        }
+    }
+
+    // 仅用于测试：直接修改空闲区域的 next_free，用于构造损坏的空闲链表
+    pub(crate) fn set_next_free(&mut self, idx: usize, next_free: Option<usize>) {
+        self.items[idx] = Entry::Free {
+            next_free,
+            generation: self.generation,
+        };
+    }
 }
 
 #[cfg(test)]
@@ -206,22 +593,26 @@ mod tests {
         assert_eq!(arena.cap(), capacity);
 
         assert_eq!(arena.free_list_head, Some(0));
-        let mut i = 0;
-        for entry in &arena.items {
+        for (i, entry) in arena.items.iter().enumerate() {
             // free_list_head = 0（0 -> 1 -> 2 -> ... -> 99）
             // 最后一块内存的 next_free 为 None
             if i == capacity - 1 {
-                assert_eq!(entry, &Entry::Free { next_free: None })
+                assert_eq!(
+                    entry,
+                    &Entry::Free {
+                        next_free: None,
+                        generation: 0
+                    }
+                )
             } else {
                 assert_eq!(
                     entry,
                     &Entry::Free {
-                        next_free: Some(i + 1)
+                        next_free: Some(i + 1),
+                        generation: 0
                     }
                 )
             }
-
-            i += 1;
         }
     }
 
@@ -281,12 +672,13 @@ mod tests {
         let to_reserve = 100;
         arena.reserve(to_reserve);
         // free_list_head = old_cap
+        // 新扩充的区域都从已经使用过的最大代数 + 1 开始
         for ele in 0..to_reserve {
             assert_eq!(
                 arena.insert(0),
                 Ok(Index {
                     idx: old_cap + ele,
-                    generation: (old_cap + ele) as u64
+                    generation: 2
                 })
             )
         }
@@ -294,6 +686,16 @@ mod tests {
         assert_eq!(arena.insert(0), Err(ArenaOOM {}));
     }
 
+    #[test]
+    fn arena_index_accessors() {
+        let mut arena = Arena::<i32>::new_with_cap(2);
+        let index = arena.insert(0).unwrap();
+        arena.remove(&index);
+        let reused = arena.insert(1).unwrap();
+        assert_eq!(reused.idx(), index.idx());
+        assert_eq!(reused.generation(), index.generation() + 1);
+    }
+
     #[test]
     fn arena_remove() {
         let mut arena = Arena::<i32>::new_with_cap(1);
@@ -317,6 +719,7 @@ mod tests {
         assert_eq!(arena.remove(&index).unwrap(), 56);
         assert!(arena.remove(&index).is_none());
 
+        // 0 号区域已经被占用过两次，新扩充的区域同样从代数 2 开始
         let current_gen = 2;
 
         let to_reserve = 5;
@@ -328,7 +731,7 @@ mod tests {
                     arena.insert(0),
                     Ok(Index {
                         idx: 0,
-                        generation: (current_gen + ele) as u64
+                        generation: current_gen
                     })
                 )
             } else {
@@ -336,10 +739,238 @@ mod tests {
                     arena.insert(0),
                     Ok(Index {
                         idx: ele + 1,
-                        generation: (current_gen + ele) as u64
+                        generation: current_gen
                     })
                 )
             }
         }
+        // 过期的 Index 仍然无效
+        assert_eq!(arena.get(&index), None);
+    }
+
+    #[test]
+    fn arena_try_reserve() {
+        let mut arena = Arena::<i32>::new_with_cap(1);
+        let index = arena.insert(0).unwrap();
+
+        assert_eq!(arena.try_reserve(usize::MAX / 2), Err(ArenaOOM {}));
+        assert_eq!(arena.cap(), 1);
+        assert_eq!(arena.insert(1), Err(ArenaOOM {}));
+        assert_eq!(arena.get(&index), Some(&0));
+
+        assert_eq!(arena.try_reserve(2), Ok(()));
+        assert_eq!(arena.cap(), 3);
+        assert_eq!(arena.free_len(), 2);
+    }
+
+    #[test]
+    fn arena_ordered() {
+        let mut arena = Arena::<i32>::new_with_cap_ordered(3);
+        for ele in 0..3 {
+            assert_eq!(arena.insert(ele).unwrap().idx, ele as usize);
+        }
+
+        let index_1 = Index {
+            idx: 1,
+            generation: 0,
+        };
+        assert_eq!(arena.remove(&index_1), Some(1));
+
+        // 空闲链表：1 -> 3 -> 4
+        arena.reserve(2);
+        assert_eq!(arena.cap(), 5);
+        assert!((0..3).map(|ele| arena.insert(ele).unwrap().idx).eq([1, 3, 4]));
+        assert_eq!(arena.insert(0), Err(ArenaOOM {}));
+
+        // 默认的 Arena 优先使用新扩容的区域：3 -> 4 -> 1
+        let mut arena = Arena::<i32>::new_with_cap(3);
+        for ele in 0..3 {
+            arena.insert(ele).unwrap();
+        }
+        arena.remove(&index_1).unwrap();
+        arena.reserve(2);
+        assert!((0..3).map(|ele| arena.insert(ele).unwrap().idx).eq([3, 4, 1]));
+    }
+
+    #[test]
+    fn arena_remove_many() {
+        let mut arena = Arena::<i32>::new_with_cap(5);
+        let indices: Vec<Index> = (0..5).map(|ele| arena.insert(ele).unwrap()).collect();
+        arena.remove(&indices[4]).unwrap();
+
+        let removed = arena.remove_many(&[indices[1], indices[3], indices[1], indices[4]]);
+        assert_eq!(removed, vec![Some(1), Some(3), None, None]);
+        assert_eq!(arena.get(&indices[1]), None);
+        assert_eq!(arena.get(&indices[3]), None);
+        assert_eq!(arena.get(&indices[0]), Some(&0));
+        assert_eq!(arena.get(&indices[2]), Some(&2));
+
+        // 空闲链表：1 -> 3 -> 4
+        assert!((0..3).map(|ele| arena.insert(ele).unwrap().idx).eq([1, 3, 4]));
+        assert_eq!(arena.insert(0), Err(ArenaOOM {}));
+
+        assert!(arena.remove_many(&[]).is_empty());
+    }
+
+    #[test]
+    fn arena_debug() {
+        let mut arena = Arena::<i32>::new_with_cap(3);
+        let index = arena.insert(10).unwrap();
+        arena.insert(11).unwrap();
+        arena.remove(&index).unwrap();
+        assert_eq!(
+            format!("{arena:?}"),
+            "Arena { cap: 3, occupied: {Index { idx: 1, generation: 0 }: 11} }"
+        );
+    }
+
+    #[test]
+    fn arena_iter() {
+        let mut arena = Arena::<i32>::new_with_cap(6);
+        assert_eq!(arena.iter().count(), 0);
+
+        let indices: Vec<Index> = (0..5).map(|ele| arena.insert(ele).unwrap()).collect();
+        arena.remove(&indices[1]).unwrap();
+        arena.remove(&indices[3]).unwrap();
+        assert!(arena
+            .iter()
+            .eq([(indices[0], &0), (indices[2], &2), (indices[4], &4)]));
+
+        // 重新占用的内存单位返回新的代数
+        let index = arena.insert(5).unwrap();
+        let survivors: Vec<_> = arena.iter().collect();
+        assert_eq!(survivors.len(), 4);
+        assert!(survivors.contains(&(index, &5)));
+        assert!(!survivors.iter().any(|(i, _)| *i == indices[1] || *i == indices[3]));
+        for (index, value) in survivors {
+            assert_eq!(arena.get(&index), Some(value));
+        }
+    }
+
+    #[test]
+    fn arena_free_len() {
+        let mut arena = Arena::<i32>::new();
+        assert_eq!((arena.free_len(), arena.occupied_len()), (0, 0));
+
+        arena.reserve(8);
+        let indices: Vec<Index> = (0..6).map(|ele| arena.insert(ele).unwrap()).collect();
+        assert_eq!((arena.free_len(), arena.occupied_len()), (2, 6));
+
+        // 在中间释放出空洞
+        arena.remove(&indices[1]).unwrap();
+        arena.remove(&indices[4]).unwrap();
+        assert_eq!((arena.free_len(), arena.occupied_len()), (4, 4));
+        assert!(arena.fragmentation() > 0.0);
+
+        arena.insert(6).unwrap();
+        arena.reserve(2);
+        assert_eq!((arena.free_len(), arena.occupied_len()), (5, 5));
+        assert_eq!(arena.free_len() + arena.occupied_len(), arena.cap());
+
+        arena.compact();
+        assert_eq!((arena.free_len(), arena.occupied_len()), (0, 5));
+    }
+
+    #[test]
+    fn arena_generation_overflow() {
+        let mut arena = Arena::<i32>::new_with_cap(2);
+        let stale = arena.insert(0).unwrap();
+        arena.remove(&stale).unwrap();
+
+        arena.set_generation(u64::MAX - 1);
+        let last = arena.insert(1).unwrap();
+        assert_eq!(last, Index { idx: 0, generation: u64::MAX - 1 });
+        assert_eq!(arena.max_generation(), u64::MAX);
+        assert_eq!(arena.get(&stale), None);
+
+        // 代数只在同一个内存单位内递增，其它内存单位不受影响
+        let other = arena.insert(2).unwrap();
+        assert_eq!(other, Index { idx: 1, generation: u64::MAX - 1 });
+        arena.remove(&other).unwrap();
+
+        // 内存单位的代数耗尽后拒绝插入，而不是回绕到 0
+        arena.remove(&last).unwrap();
+        assert_eq!(arena.insert(3), Err(ArenaOOM {}));
+        assert_eq!(arena.free_len(), 2);
+        assert_eq!(arena.occupied_len(), 0);
+        assert_eq!(arena.get(&stale), None);
+        assert_eq!(arena.get(&last), None);
+        assert_eq!(arena.get(&other), None);
+    }
+
+    #[test]
+    fn arena_check_integrity() {
+        let mut arena = Arena::<i32>::new_with_cap(4);
+        let indices: Vec<Index> = (0..3).map(|ele| arena.insert(ele).unwrap()).collect();
+        arena.remove(&indices[0]).unwrap();
+        arena.remove(&indices[2]).unwrap();
+        // 空闲链表：2 -> 0 -> 3
+        assert_eq!(arena.check_integrity(), Ok(()));
+
+        // 成环：2 -> 0 -> 2
+        let mut broken = arena.clone();
+        broken.set_next_free(0, Some(2));
+        assert!(broken.check_integrity().unwrap_err().contains("twice"));
+
+        // 指向被占用的区域
+        let mut broken = arena.clone();
+        broken.set_next_free(0, Some(1));
+        assert!(broken.check_integrity().unwrap_err().contains("occupied"));
+
+        // 越界
+        let mut broken = arena.clone();
+        broken.set_next_free(0, Some(10));
+        assert!(broken.check_integrity().unwrap_err().contains("out of range"));
+
+        // 提前断开，丢失了空闲区域 3
+        let mut broken = arena.clone();
+        broken.set_next_free(0, None);
+        assert!(broken.check_integrity().unwrap_err().contains("add up"));
+    }
+
+    #[test]
+    fn arena_compact() {
+        let mut arena = Arena::<i32>::new_with_cap(6);
+        let indices: Vec<Index> = (0..5).map(|ele| arena.insert(ele).unwrap()).collect();
+        arena.remove(&indices[0]).unwrap();
+        arena.remove(&indices[3]).unwrap();
+
+        let remap = arena.compact();
+        assert_eq!(arena.cap(), 3);
+        assert_eq!(
+            remap.iter().map(|(old, _)| *old).collect::<Vec<_>>(),
+            vec![indices[1], indices[2], indices[4]]
+        );
+        assert!(remap.iter().map(|(_, new)| new.idx).eq([0, 1, 2]));
+        for ((old, new), value) in remap.iter().zip([1, 2, 4]) {
+            assert_eq!(arena.get(new), Some(&value));
+            // 被移动的数据使用新的代数
+            assert!(new.generation > old.generation);
+        }
+        // 旧的 Index 失效，包括指向新位置上原有数据的过期 Index
+        assert_eq!(arena.get(&indices[4]), None);
+        assert_eq!(arena.get(&indices[0]), None);
+        assert_eq!(arena.get(&indices[1]), None);
+
+        // 没有空闲区域，扩容后才能继续插入
+        assert_eq!(arena.insert(5), Err(ArenaOOM {}));
+        arena.reserve(1);
+        assert_eq!(arena.insert(5).unwrap().idx, 3);
+    }
+
+    #[test]
+    fn arena_shrink_to_fit() {
+        let mut arena = Arena::<i32>::new_with_cap(100);
+        let indices: Vec<Index> = (0..100).map(|ele| arena.insert(ele).unwrap()).collect();
+        for index in indices.iter().filter(|index| index.idx % 10 != 0) {
+            arena.remove(index).unwrap();
+        }
+
+        let remap = arena.shrink_to_fit();
+        assert_eq!(arena.cap(), 10);
+        assert!(arena.items.capacity() < 100);
+        for (i, (_, new)) in remap.iter().enumerate() {
+            assert_eq!(arena.get(new), Some(&(i as i32 * 10)));
+        }
     }
 }
@@ -0,0 +1,152 @@
+use std::future::Future;
+use std::hash::Hash;
+
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::lru::cache::Cache;
+use crate::lru::err::CacheError;
+
+// 通过 tokio 的 Mutex 在多个异步任务之间共享的 Cache
+// 与 SyncCache 相同，无法在释放锁后返回引用，因此 query 等方法返回 value 的拷贝（要求 V: Clone）
+pub struct AsyncCache<K, V>
+where
+    K: Eq + Hash,
+{
+    inner: Mutex<Cache<K, V>>,
+}
+
+impl<K, V> AsyncCache<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    pub fn new(cache: Cache<K, V>) -> Self {
+        AsyncCache {
+            inner: Mutex::new(cache),
+        }
+    }
+
+    pub async fn insert(&self, key: K, value: V) -> Result<Option<V>, CacheError> {
+        self.lock().await.insert(key, value)
+    }
+
+    pub async fn query(&self, key: &K) -> Result<V, CacheError>
+    where
+        V: Clone,
+    {
+        self.lock().await.query(key).cloned()
+    }
+
+    pub async fn remove(&self, key: &K) -> Result<V, CacheError> {
+        self.lock().await.remove(key)
+    }
+
+    pub async fn len(&self) -> usize {
+        self.lock().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.lock().await.is_empty()
+    }
+
+    pub async fn contains_key(&self, key: &K) -> bool {
+        self.lock().await.contains_key(key)
+    }
+
+    // key 存在时返回其 value 的拷贝（移动到头部）
+    // 不存在时等待 f() 的结果并插入，等待期间不持有锁，因此不同 key 的未命中不会互相阻塞
+    // 同一个 key 同时未命中时每个任务都会执行 f，最先写回的结果会被保留并返回给其它任务
+    pub async fn get_or_insert_with<F, Fut>(&self, key: K, f: F) -> Result<V, CacheError>
+    where
+        V: Clone,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = V>,
+    {
+        if let Some(value) = self.lock().await.query_opt(&key)? {
+            return Ok(value.clone());
+        }
+
+        let value = f().await;
+
+        let mut cache = self.lock().await;
+        if let Some(value) = cache.query_opt(&key)? {
+            return Ok(value.clone());
+        }
+        cache.insert(key, value.clone())?;
+        Ok(value)
+    }
+
+    async fn lock(&self) -> MutexGuard<'_, Cache<K, V>> {
+        self.inner.lock().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn async_cache_basic() {
+        let cache = AsyncCache::new(Cache::<i32, String>::new_with_cap(2));
+        assert_eq!(cache.insert(1, "a".to_string()).await, Ok(None));
+        assert_eq!(cache.insert(2, "b".to_string()).await, Ok(None));
+        assert_eq!(cache.query(&1).await, Ok("a".to_string()));
+
+        // [3-c 1-a]
+        cache.insert(3, "c".to_string()).await.unwrap();
+        assert!(!cache.contains_key(&2).await);
+        assert_eq!(cache.remove(&1).await, Ok("a".to_string()));
+        assert_eq!(cache.len().await, 1);
+        assert_eq!(cache.query(&1).await, Err(CacheError::CacheMiss));
+    }
+
+    #[tokio::test]
+    async fn async_cache_get_or_insert_with() {
+        let cache = Arc::new(AsyncCache::new(Cache::<i32, i32>::new_with_cap(4)));
+        let calls = AtomicUsize::new(0);
+
+        let value = cache
+            .get_or_insert_with(1, || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                10
+            })
+            .await;
+        assert_eq!(value, Ok(10));
+        let value = cache
+            .get_or_insert_with(1, || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                -1
+            })
+            .await;
+        assert_eq!(value, Ok(10));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // 等待 f 期间不持有锁，其它任务可以正常访问缓存
+        let (entered_tx, entered_rx) = oneshot::channel();
+        let (release_tx, release_rx) = oneshot::channel::<()>();
+        let slow = {
+            let cache = Arc::clone(&cache);
+            tokio::spawn(async move {
+                cache
+                    .get_or_insert_with(2, || async {
+                        entered_tx.send(()).unwrap();
+                        release_rx.await.unwrap();
+                        20
+                    })
+                    .await
+            })
+        };
+        entered_rx.await.unwrap();
+        assert!(cache.inner.try_lock().is_ok());
+        assert_eq!(cache.query(&1).await, Ok(10));
+        cache.insert(3, 30).await.unwrap();
+        release_tx.send(()).unwrap();
+
+        assert_eq!(slow.await.unwrap(), Ok(20));
+        assert_eq!(cache.query(&2).await, Ok(20));
+    }
+}
@@ -1,13 +1,289 @@
-use std::collections::HashMap;
-use std::hash::Hash;
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::Hash;
+use core::mem;
+use core::ops::{self, Deref, DerefMut};
+#[cfg(feature = "std")]
+use std::sync::mpsc;
+#[cfg(feature = "std")]
 use std::time;
-use crate::lru::list::LinkedList;
+#[cfg(feature = "std")]
+use crate::lru::clock::{Clock, SystemClock};
+use crate::lru::HashMap;
+use crate::lru::lfu::LfuCache;
+use crate::lru::list::{LinkedList, Node};
+#[cfg(feature = "std")]
+use crate::lru::list::ExpirationPolicy;
+use crate::lru::sketch::FrequencySketch;
 use crate::lru::arena::Index;
-use crate::lru::err::CacheError;
+use crate::lru::err::{ArenaOOM, CacheError, CacheOrUserError, ListError};
 
+#[derive(Clone)]
 struct ListItem<K, V> {
     pub key: K,
     pub value: V,
+    // 插入序号，覆盖写入时保持不变
+    pub seq: u64,
+    // 首次插入的时间，覆盖写入时保持不变
+    #[cfg(feature = "std")]
+    pub created_at: time::Instant,
+    // 写入时计算的权重，没有设置 max_weight 时为 0
+    pub weight: usize,
+    // 被访问（命中的 query 和覆盖写入）的次数，插入时为 1，LFU 策略下据此淘汰
+    pub freq: u64,
+}
+
+// 导出的单条数据及其时间信息
+// Instant 只在当前进程内有意义，因此只能导入到同一进程中的缓存
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportEntry<K, V> {
+    pub key: K,
+    pub value: V,
+    // 首次插入的时间
+    pub created_at: time::Instant,
+    // 最近一次被插入或访问的时间
+    pub last_accessed: time::Instant,
+    // 导出时剩余的存活时间，为 None 说明不会超时淘汰
+    pub ttl: Option<time::Duration>,
+}
+
+// 查询的命中统计
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    // 命中率，没有任何查询时为 0
+    pub hit_rate: f64,
+}
+
+// 默认保留的利用率采样个数
+#[cfg(feature = "std")]
+const DEFAULT_SAMPLE_RING_SIZE: usize = 64;
+
+// 缓存已满时插入新数据的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GrowthPolicy {
+    // 按照淘汰策略淘汰数据，默认淘汰最久未访问的数据
+    #[default]
+    EvictLru,
+    // 将容量扩大一倍而不是淘汰数据，直到容量达到 max 后再按照 EvictLru 淘汰
+    // max 为 None 说明不限制容量
+    Grow { max: Option<usize> },
+    // 不淘汰任何数据，新数据超出容量（或者设置了 max_weight 时超出权重上限）时返回 CapacityExceeded
+    // 调用方可以据此进行限流或者主动删除数据；暂停淘汰（freeze）期间仍然通过扩容容纳新数据
+    // upsert 等原地修改已有数据的方法无法回滚修改，重新计算权重后超过上限时同样返回 CapacityExceeded，
+    // 但修改仍然生效，此时不会淘汰其它数据，之后的插入会被拒绝直到总权重回到上限以内
+    Reject,
+}
+
+// 容量已满时选择被淘汰数据的策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    // 淘汰最久未访问的数据（链表尾部）
+    #[default]
+    Lru,
+    // 淘汰访问次数最少的数据，次数相同时淘汰最久未访问的
+    // 需要遍历所有数据才能找到被淘汰的数据
+    Lfu,
+}
+
+// 数据被淘汰的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionReason {
+    // 容量已满时按照淘汰策略淘汰
+    Capacity,
+    // 超时淘汰
+    Expired,
+}
+
+// 带有新鲜度的查询结果
+#[derive(Debug, PartialEq)]
+pub enum Freshness<'a, V> {
+    // 尚未过期
+    Fresh(&'a V),
+    // 已过期但仍在宽限期内，调用方应当在后台刷新该数据
+    Stale(&'a V),
+    // 不存在或宽限期已结束
+    Miss,
+}
+
+// 查询时的可选行为
+// 默认（Default）与 query 的行为一致：移动到头部、刷新淘汰时间、计入命中统计
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryOpts {
+    // 是否将数据移动到头部
+    pub reposition: bool,
+    // 是否按照 timeout 刷新数据的淘汰时间
+    pub refresh_ttl: bool,
+    // 是否计入命中/未命中统计
+    pub count_stats: bool,
+}
+
+impl Default for QueryOpts {
+    fn default() -> Self {
+        QueryOpts {
+            reposition: true,
+            refresh_ttl: true,
+            count_stats: true,
+        }
+    }
+}
+
+// 单个数据的存活时间，为 None 时按照 timeout 计算
+// 没有 std 时不会超时淘汰，存活时间只能为 None
+#[cfg(feature = "std")]
+type Ttl = Option<time::Duration>;
+#[cfg(not(feature = "std"))]
+type Ttl = Option<core::convert::Infallible>;
+
+// 计算 value 的大小（权重）
+pub type Weigher<V> = Arc<dyn Fn(&V) -> usize + Send + Sync>;
+
+// 数据被淘汰时的回调，获得被淘汰数据的所有权
+pub type EvictCallback<K, V> = Box<dyn FnMut(K, V) + Send>;
+
+// 淘汰事件的订阅
+// 同时注册了淘汰回调时，回调获得被淘汰数据的所有权，通过 clone_value 拷贝一份 value 发送
+#[cfg(feature = "std")]
+struct EvictionSubscriber<K, V> {
+    tx: mpsc::Sender<(K, V, EvictionReason)>,
+    clone_value: fn(&V) -> V,
+}
+
+// 链式配置并创建 Cache，没有设置的选项与 new_with_cap 的行为一致
+// 没有调用 capacity 时容量为 0
+pub struct CacheBuilder<K, V> {
+    cap: usize,
+    #[cfg(feature = "std")]
+    timeout: Option<time::Duration>,
+    #[cfg(feature = "std")]
+    expiration: ExpirationPolicy,
+    growth: GrowthPolicy,
+    eviction: EvictionPolicy,
+    on_evict: Option<EvictCallback<K, V>>,
+    #[cfg(feature = "std")]
+    clock: Arc<dyn Clock>,
+    #[cfg(feature = "std")]
+    max_idle: Option<time::Duration>,
+    #[cfg(feature = "std")]
+    grace: Option<time::Duration>,
+}
+
+impl<K, V> Default for CacheBuilder<K, V> {
+    fn default() -> Self {
+        CacheBuilder {
+            cap: 0,
+            #[cfg(feature = "std")]
+            timeout: None,
+            #[cfg(feature = "std")]
+            expiration: ExpirationPolicy::default(),
+            growth: GrowthPolicy::default(),
+            eviction: EvictionPolicy::default(),
+            on_evict: None,
+            #[cfg(feature = "std")]
+            clock: Arc::new(SystemClock),
+            #[cfg(feature = "std")]
+            max_idle: None,
+            #[cfg(feature = "std")]
+            grace: None,
+        }
+    }
+}
+
+impl<K, V> CacheBuilder<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn capacity(mut self, cap: usize) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    // 数据在 timeout 时间内没有被访问会被淘汰
+    #[cfg(feature = "std")]
+    pub fn timeout(mut self, timeout: time::Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    // 淘汰时间的计算方式，只在设置了 timeout 时生效
+    #[cfg(feature = "std")]
+    pub fn expiration(mut self, policy: ExpirationPolicy) -> Self {
+        self.expiration = policy;
+        self
+    }
+
+    pub fn growth(mut self, growth: GrowthPolicy) -> Self {
+        self.growth = growth;
+        self
+    }
+
+    pub fn eviction(mut self, policy: EvictionPolicy) -> Self {
+        self.eviction = policy;
+        self
+    }
+
+    // 与 Cache::set_max_idle 相同
+    #[cfg(feature = "std")]
+    pub fn max_idle(mut self, max_idle: time::Duration) -> Self {
+        self.max_idle = Some(max_idle);
+        self
+    }
+
+    // 与 Cache::set_grace 相同
+    #[cfg(feature = "std")]
+    pub fn grace(mut self, grace: time::Duration) -> Self {
+        self.grace = Some(grace);
+        self
+    }
+
+    // 与 Cache::set_clock 相同
+    #[cfg(feature = "std")]
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    // 与 Cache::on_evict 相同
+    pub fn on_evict(mut self, f: impl FnMut(K, V) + Send + 'static) -> Self {
+        self.on_evict = Some(Box::new(f));
+        self
+    }
+
+    pub fn build(self) -> Cache<K, V> {
+        #[cfg(feature = "std")]
+        let list = {
+            let mut list = match self.timeout {
+                Some(timeout) => LinkedList::new_with_cap_timeout(self.cap, timeout),
+                None => LinkedList::new_with_cap(self.cap),
+            };
+            list.set_expiration_policy(self.expiration);
+            list.set_clock(self.clock);
+            if let Some(max_idle) = self.max_idle {
+                list.set_max_idle(max_idle);
+            }
+            if let Some(grace) = self.grace {
+                list.set_grace(grace);
+            }
+            list
+        };
+        #[cfg(not(feature = "std"))]
+        let list = LinkedList::new_with_cap(self.cap);
+        let mut cache = Cache::from_list(list);
+        cache.growth = self.growth;
+        cache.eviction = self.eviction;
+        cache.on_evict = self.on_evict;
+        cache
+    }
 }
 
 pub struct Cache<K, V>
@@ -16,185 +292,4885 @@ where
 {
     list: LinkedList<ListItem<K, V>>,
     map: HashMap<K, Index>,
+    // 缓存的容量
+    // 暂停淘汰期间链表可能扩容，此时链表的容量会大于 cap
+    cap: usize,
+    // 是否暂停了容量淘汰和超时淘汰
+    frozen: bool,
+    // 单个 value 允许的最大大小及其计算方式
+    // 为 None 说明不限制
+    max_value_size: Option<(usize, Weigher<V>)>,
+    // 所有数据的总权重上限及其计算方式
+    // 为 None 说明只按照数据个数淘汰
+    max_weight: Option<(usize, Weigher<V>)>,
+    // 当前所有数据的权重之和
+    current_weight: usize,
+    // 下一个新插入数据的序号
+    next_seq: u64,
+    // 最近的 (采样时间, 数据个数) 采样，最多保留 sample_ring_size 个
+    #[cfg(feature = "std")]
+    samples: VecDeque<(time::Instant, usize)>,
+    #[cfg(feature = "std")]
+    sample_ring_size: usize,
+    // 自动采样的间隔，为 None 说明只能通过 sample() 手动采样
+    #[cfg(feature = "std")]
+    sample_interval: Option<time::Duration>,
+    // 按顺序记录的被 query 的 key，最多保留 access_log_size 个
+    // 为 None 说明没有开启记录
+    access_log: Option<VecDeque<K>>,
+    access_log_size: usize,
+    // 淘汰事件的订阅，为 None 说明没有订阅淘汰事件
+    #[cfg(feature = "std")]
+    eviction_tx: Option<EvictionSubscriber<K, V>>,
+    // 淘汰回调，为 None 说明没有注册回调
+    on_evict: Option<EvictCallback<K, V>>,
+    // 查询命中和未命中的次数
+    hits: u64,
+    misses: u64,
+    // 碎片程度超过该阈值后自动整理内存，为 None 说明不自动整理
+    auto_compact_threshold: Option<f32>,
+    // insert 等写入操作之前是否自动执行超时淘汰
+    auto_retire: bool,
+    // 缓存已满时扩容还是淘汰
+    growth: GrowthPolicy,
+    // 容量已满时淘汰哪一个数据
+    eviction: EvictionPolicy,
+    // 准入策略使用的访问频率统计，为 None 说明没有开启准入策略
+    admission: Option<FrequencySketch>,
+    // get_or_insert_with 等计算得到但没有通过准入策略的 value
+    // 暂存在这里以便不缓存也能返回其引用，下一次被拒绝时覆盖
+    uncached: Option<V>,
+    // 自创建以来因容量和超时被淘汰的数据个数
+    evicted: u64,
+    expired: u64,
 }
 
 impl<K, V> Cache<K, V>
 where
-    K: Eq + Hash + Copy,
+    K: Eq + Hash + Clone,
 {
+    pub fn builder() -> CacheBuilder<K, V> {
+        CacheBuilder::new()
+    }
+
+    // 容量为 0、不带有超时淘汰机制的缓存
+    // 插入数据之前需要先扩容（reserve），否则 insert 会返回 ZeroCapacity
+    pub fn new() -> Self {
+        CacheBuilder::new().build()
+    }
+
     pub fn new_with_cap(cap: usize) -> Self {
-        Cache {
-            list: LinkedList::new_with_cap(cap),
-            map: HashMap::new(),
-        }
+        CacheBuilder::new().capacity(cap).build()
     }
 
+    #[cfg(feature = "std")]
     pub fn new_with_cap_timeout(cap: usize, timeout: time::Duration) -> Self {
+        CacheBuilder::new().capacity(cap).timeout(timeout).build()
+    }
+
+    // 与 new_with_cap_timeout 相同，并指定数据被访问时淘汰时间的计算方式
+    // Sliding：每次访问都会延长淘汰时间（与 new_with_cap_timeout 相同）
+    // Fixed：淘汰时间在 insert 时确定，之后的访问不会延长；重新 insert 会重新计算
+    #[cfg(feature = "std")]
+    pub fn new_with_cap_timeout_policy(
+        cap: usize,
+        timeout: time::Duration,
+        policy: ExpirationPolicy,
+    ) -> Self {
+        CacheBuilder::new()
+            .capacity(cap)
+            .timeout(timeout)
+            .expiration(policy)
+            .build()
+    }
+
+    // 与 new_with_cap 相同，并指定缓存已满时的处理方式
+    // Grow 策略下 cap 为初始容量，capacity() 返回扩容后的容量
+    pub fn new_with_cap_growth(cap: usize, growth: GrowthPolicy) -> Self {
+        CacheBuilder::new().capacity(cap).growth(growth).build()
+    }
+
+    // 从 HashMap 创建缓存，容量为 map 中数据的个数，不带有超时淘汰机制
+    // HashMap 本身无序，因此缓存中的访问顺序是任意的
+    pub fn from_hashmap(map: HashMap<K, V>) -> Self {
+        let mut cache = Self::new_with_cap(map.len());
+        for (key, value) in map {
+            // 容量与数据个数相同且没有超时、权重和准入限制，插入不会失败
+            cache
+                .insert(key, value)
+                .expect("cache sized to the map rejected an insert");
+        }
+        cache
+    }
+
+    // 将缓存中的所有数据复制到一个新的 HashMap 中（不保留访问顺序）
+    pub fn to_hashmap(&self) -> HashMap<K, V>
+    where
+        V: Clone,
+    {
+        self.list
+            .iter()
+            .map(|item| (item.key.clone(), item.value.clone()))
+            .collect()
+    }
+
+    fn from_list(list: LinkedList<ListItem<K, V>>) -> Self {
         Cache {
-            list: LinkedList::new_with_cap_timeout(cap, timeout),
+            cap: list.capacity(),
+            frozen: false,
+            list,
             map: HashMap::new(),
+            max_value_size: None,
+            max_weight: None,
+            current_weight: 0,
+            next_seq: 0,
+            #[cfg(feature = "std")]
+            samples: VecDeque::new(),
+            #[cfg(feature = "std")]
+            sample_ring_size: DEFAULT_SAMPLE_RING_SIZE,
+            #[cfg(feature = "std")]
+            sample_interval: None,
+            access_log: None,
+            access_log_size: 0,
+            #[cfg(feature = "std")]
+            eviction_tx: None,
+            on_evict: None,
+            hits: 0,
+            misses: 0,
+            auto_compact_threshold: None,
+            auto_retire: true,
+            growth: GrowthPolicy::default(),
+            eviction: EvictionPolicy::default(),
+            admission: None,
+            uncached: None,
+            evicted: 0,
+            expired: 0,
         }
     }
 
-    pub fn query(&mut self, key: &K) -> Result<&V, CacheError> {
-        let index = self.map.get(key).ok_or(CacheError::CacheMiss)?;
-        let index = self.list
-            .reposition_to_head(index)
-            .map_err(CacheError::CacheBroken)?;
-        let node = self.list.get(&index).map_err(CacheError::CacheBroken)?;
-        // 更新 map 中的 index
-        self.map.insert(*key, index);
-        Ok(&node.value.value)
+    // 限制单个 value 的大小，大小由 weigher 计算
+    // 超过限制的 value 在 insert 时会被拒绝，且不会淘汰任何数据
+    pub fn set_max_value_size(
+        &mut self,
+        max: usize,
+        weigher: impl Fn(&V) -> usize + Send + Sync + 'static,
+    ) {
+        self.max_value_size = Some((max, Arc::new(weigher)));
     }
 
-    pub fn remove(&mut self, key: &K) -> Result<V, CacheError> {
-        let index = self.map.remove(key).ok_or(CacheError::CacheMiss)?;
-        let item = self.list.remove(&index).map_err(CacheError::CacheBroken)?;
-        Ok(item.value)
+    // 按照权重限制缓存的大小，每个 value 的权重由 weigher 计算
+    // 插入数据后所有数据的权重之和超过 max 时，从尾部淘汰数据直到不超过 max（按容量淘汰处理）
+    // 权重超过 max 的 value 在 insert 时会被拒绝，且不会淘汰任何数据
+    // 数据个数仍然受 cap 的限制；已有的数据会重新计算权重，超出的部分立即淘汰
+    // 注意：权重只在 insert、upsert 和 extend_with 时计算，通过 get_mut 等方法原地修改 value 不会重新计算
+    pub fn set_max_weight(
+        &mut self,
+        max: usize,
+        weigher: impl Fn(&V) -> usize + Send + Sync + 'static,
+    ) -> Result<(), CacheError> {
+        let weigher: Weigher<V> = Arc::new(weigher);
+        self.current_weight = 0;
+        for item in self.list.iter_mut() {
+            item.weight = weigher(&item.value);
+            self.current_weight += item.weight;
+        }
+        self.max_weight = Some((max, weigher));
+        self.evict_overweight(0)
     }
 
-    pub fn insert(&mut self, key: K, value: V) -> Result<(), CacheError> {
-        // 每次插入之前都进行自动淘汰
-        // TODO: 变为无阻塞操作
-        self.retire()?;
+    // 当前所有数据的权重之和，没有设置 max_weight 时为 0
+    pub fn current_weight(&self) -> usize {
+        self.current_weight
+    }
 
-        if let Some(index) = self.map.get(&key) {
-            let index = self.list
-                .reposition_to_head(index)
-                .map_err(CacheError::CacheBroken)?;
-            let item = self
-                .list
-                .get_mut(&index)
-                .map_err(CacheError::CacheBroken)?;
-            // 更新 map 中的 index
-            self.map.insert(key, index);
-            item.value.value = value;
-            return Ok(());
+    fn weigh(&self, value: &V) -> usize {
+        match &self.max_weight {
+            Some((_, weigher)) => weigher(value),
+            None => 0,
         }
+    }
 
-        if self.list.is_full() {
-            let item = self.list.pop_back().map_err(CacheError::CacheBroken)?;
-            self.map.remove(&item.key);
+    // 原地修改位于头部的 index 之后重新计算其权重，并从尾部淘汰超出的部分
+    // 不会淘汰 index 本身，即使它的权重已经超过上限
+    // Reject 策略下不淘汰其它数据，超过上限时返回 CapacityExceeded（修改已经生效，无法回滚）
+    fn reweigh(&mut self, index: &Index) -> Result<(), CacheError> {
+        let max = match &self.max_weight {
+            Some((max, _)) => *max,
+            None => return Ok(()),
+        };
+        let node = self.list.get(index)?;
+        let weight = self.weigh(&node.value.value);
+        let rejected = self.reject_overweight(weight, node.value.weight);
+        let item = &mut self.list.get_mut(index)?.value;
+        self.current_weight = self.current_weight - item.weight + weight;
+        item.weight = weight;
+        if self.frozen {
+            return Ok(());
+        }
+        if self.growth == GrowthPolicy::Reject {
+            return rejected;
+        }
+        while self.current_weight > max && self.list.len() > 1 {
+            self.evict_one()?;
         }
+        Ok(())
+    }
 
-        let index = self
-            .list
-            .push_front(ListItem { key, value })
-            .map_err(CacheError::CacheBroken)?;
-        self.map.insert(key, index);
+    // Reject 策略下，释放 released 的权重并加入 weight 的权重后超过上限时返回 CapacityExceeded
+    // 暂停淘汰期间不检查
+    fn reject_overweight(&self, weight: usize, released: usize) -> Result<(), CacheError> {
+        let max = match &self.max_weight {
+            Some((max, _)) => *max,
+            None => return Ok(()),
+        };
+        if self.frozen || self.growth != GrowthPolicy::Reject {
+            return Ok(());
+        }
+        let available = max.saturating_sub(self.current_weight - released);
+        if weight > available {
+            return Err(CacheError::CapacityExceeded {
+                needed: weight,
+                available,
+            });
+        }
+        Ok(())
+    }
 
+    // 从尾部淘汰数据，直到再加入 extra 的权重后总权重不超过上限
+    // 暂停淘汰期间不淘汰
+    fn evict_overweight(&mut self, extra: usize) -> Result<(), CacheError> {
+        let max = match &self.max_weight {
+            Some((max, _)) => *max,
+            None => return Ok(()),
+        };
+        if self.frozen {
+            return Ok(());
+        }
+        while self.current_weight + extra > max && !self.list.is_empty() {
+            self.evict_one()?;
+        }
         Ok(())
     }
 
-    pub fn len(&self) -> usize {
-        self.list.len()
+    #[must_use = "query returns the value or CacheMiss; use touch to only refresh recency"]
+    pub fn query(&mut self, key: &K) -> Result<&V, CacheError> {
+        self.query_opts(key, QueryOpts::default())
     }
 
-    fn retire(&mut self) -> Result<(), CacheError> {
-        let retired_items = self.list.retire()
-            .map_err(CacheError::CacheBroken)?;
-        if let Some(items) = retired_items {
-            for item in &items {
-                self.map.remove(&item.key).ok_or(CacheError::CacheMiss)?;
+    // 开启基于访问频率的准入策略（TinyLFU），width 和 depth 为频率统计的大小
+    // 容量已满时，只有新 key 的估计访问频率高于链表尾部的数据时才会淘汰尾部数据并插入，
+    // 否则返回 NotAdmitted，缓存保持不变
+    // 命中的 query 和每次 insert（包括被拒绝的）计入访问频率，因此未命中后再 insert 只计一次
+    // 频率会定期减半以适应访问模式的变化
+    // 只影响按数据个数的淘汰，不影响按权重的淘汰
+    pub fn set_admission_sketch(&mut self, width: usize, depth: usize) {
+        self.admission = Some(FrequencySketch::new(width, depth));
+    }
+
+    // 数据超过 max_idle 没有被访问（query、touch 等）时也会被淘汰，与 timeout 相互独立
+    // 空闲的数据不一定位于链表尾部，开启后每次超时淘汰都会遍历所有数据
+    #[cfg(feature = "std")]
+    pub fn set_max_idle(&mut self, max_idle: time::Duration) {
+        self.list.set_max_idle(max_idle);
+    }
+
+    // 替换超时淘汰、访问时间和利用率采样使用的时间来源，默认为系统时间
+    // 已有数据的淘汰时间不变
+    #[cfg(feature = "std")]
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.list.set_clock(clock);
+    }
+
+    // 与 query 相同，但返回 value 的可变引用，可以原地修改缓存中的数据
+    pub fn get_mut(&mut self, key: &K) -> Result<&mut V, CacheError> {
+        self.query(key)?;
+        let index = self.map.get(key).ok_or(CacheError::CacheMiss)?;
+        let node = self.list.get_mut(index)?;
+        Ok(&mut node.value.value)
+    }
+
+    // 按照 opts 查询 key，可以分别控制是否移动到头部、是否刷新淘汰时间、是否计入统计
+    pub fn query_opts(&mut self, key: &K, opts: QueryOpts) -> Result<&V, CacheError> {
+        self.record_access(key);
+        self.maybe_sample();
+        let index = match self.map.get(key) {
+            Some(index) => *index,
+            None => {
+                if opts.count_stats {
+                    self.misses += 1;
+                }
+                return Err(CacheError::CacheMiss);
+            }
+        };
+        if opts.count_stats {
+            self.hits += 1;
+        }
+        if let Some(sketch) = &mut self.admission {
+            sketch.increment(key);
+        }
+        let item = &mut self.list.get_mut(&index)?.value;
+        item.freq = item.freq.saturating_add(1);
+
+        // Fixed 策略下访问不会延长淘汰时间
+        #[cfg(feature = "std")]
+        {
+            let refresh_ttl =
+                opts.refresh_ttl && self.list.expiration_policy() == ExpirationPolicy::Sliding;
+            // 原地移动到头部，index 保持不变，不需要更新 map
+            if opts.reposition {
+                self.list.move_to_head_with(&index, refresh_ttl)?;
+            } else if refresh_ttl {
+                self.list.refresh_expire_time(&index)?;
+            }
+        }
+        // 没有 std 时不存在淘汰时间，只需要移动到头部
+        #[cfg(not(feature = "std"))]
+        if opts.reposition {
+            self.list.move_to_head(&index)?;
+        }
+        let node = self.list.get(&index)?;
+        Ok(&node.value.value)
+    }
+
+    // 设置过期后的宽限时间，宽限期内的数据仍然可以通过 query_with_grace 读取
+    // 对之后插入或访问的数据生效
+    #[cfg(feature = "std")]
+    pub fn set_grace(&mut self, grace: time::Duration) {
+        self.list.set_grace(grace);
+    }
+
+    // 查询 key 并返回其新鲜度，与 query 相同计入命中/未命中统计（Stale 计为命中）
+    // Fresh：与 query 相同，数据会被移动到头部
+    // Stale：不会移动数据，也不会刷新过期时间，调用方应当重新 insert
+    // Miss：key 不存在，或者宽限期已结束、空闲超过 max_idle（此时数据会被删除）
+    #[cfg(feature = "std")]
+    pub fn query_with_grace(&mut self, key: &K) -> Result<Freshness<'_, V>, CacheError> {
+        if let Some(index) = self.map.get(key).copied() {
+            let now = self.list.now();
+            let node = self.list.get(&index)?;
+            if self.list.is_retirable(node, now) {
+                let (k, v) = self.remove_entry(key)?;
+                self.expired += 1;
+                self.notify_evicted(k, v, EvictionReason::Expired);
+            } else if self.is_expired(node, now) {
+                let opts = QueryOpts {
+                    reposition: false,
+                    refresh_ttl: false,
+                    count_stats: true,
+                };
+                return self.query_opts(key, opts).map(Freshness::Stale);
             }
         }
+        match self.query(key) {
+            Ok(value) => Ok(Freshness::Fresh(value)),
+            Err(CacheError::CacheMiss) => Ok(Freshness::Miss),
+            Err(err) => Err(err),
+        }
+    }
+
+    // 查询 key 对应的 value，但不会将其移动到头部，也不计入命中统计
+    pub fn peek(&self, key: &K) -> Result<&V, CacheError> {
+        let index = self.map.get(key).ok_or(CacheError::CacheMiss)?;
+        let node = self.list.get(index)?;
+        Ok(&node.value.value)
+    }
+
+    // 将 key 移动到头部并按照 timeout 重新计算淘汰时间，不返回 value，也不计入命中统计
+    // Fixed 策略下只移动到头部，不会延长淘汰时间
+    pub fn touch(&mut self, key: &K) -> Result<(), CacheError> {
+        let index = self.map.get(key).ok_or(CacheError::CacheMiss)?;
+        self.list.move_to_head(index)?;
         Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::thread;
+    // key 距离过期的剩余时间，已过期但还没有被淘汰时返回 0
+    // 该数据没有淘汰时间（例如缓存没有设置 timeout）时返回 None
+    #[cfg(feature = "std")]
+    pub fn ttl(&self, key: &K) -> Result<Option<time::Duration>, CacheError> {
+        let index = self.map.get(key).ok_or(CacheError::CacheMiss)?;
+        let node = self.list.get(index)?;
+        Ok(node
+            .expire_time()
+            .map(|expire_time| expire_time.saturating_duration_since(self.list.now())))
+    }
 
-    use crate::lru::err::ListError;
+    // key 距离最近一次被插入或访问（query、touch 等移动到头部的操作）的时间
+    // 与 ttl 不同，没有设置 timeout 时同样有效
+    #[cfg(feature = "std")]
+    pub fn idle_time(&self, key: &K) -> Result<time::Duration, CacheError> {
+        let index = self.map.get(key).ok_or(CacheError::CacheMiss)?;
+        let node = self.list.get(index)?;
+        Ok(self.list.now().saturating_duration_since(node.last_access()))
+    }
 
-    use super::*;
+    // 最久未访问的数据，LRU 策略下即下一个会被容量淘汰的数据，不会改变访问顺序
+    pub fn peek_lru(&self) -> Option<(&K, &V)> {
+        let item = self.list.peek_back().ok()?;
+        Some((&item.key, &item.value))
+    }
 
-    #[test]
-    fn lru_cache_consistency() {
-        let mut lru_cache = Cache::<i32, i32>::new_with_cap(0);
-        assert_eq!(
-            lru_cache.insert(0, 0),
-            Err(CacheError::CacheBroken(ListError::ListEmpty))
-        );
+    // 最近访问的数据，不会改变访问顺序
+    pub fn peek_mru(&self) -> Option<(&K, &V)> {
+        let item = self.list.peek_front().ok()?;
+        Some((&item.key, &item.value))
+    }
 
-        let mut lru_cache = Cache::<i32, i32>::new_with_cap(2);
-        // [1-1]
-        assert!(lru_cache.insert(1, 1).is_ok());
-        // [2-2 1-1]
-        assert!(lru_cache.insert(2, 2).is_ok());
-        // [1-1 2-2]
-        assert_eq!(lru_cache.query(&1), Ok(&1));
-        // [3-3 1-1]
-        assert!(lru_cache.insert(3, 3).is_ok());
-        assert_eq!(lru_cache.query(&2), Err(CacheError::CacheMiss));
-        // [1,-1 3-3]
-        assert!(lru_cache.insert(1, -1).is_ok());
-        assert_eq!(lru_cache.query(&1), Ok(&-1));
-        // [4-4 1,-1]
-        assert!(lru_cache.insert(4, 4).is_ok());
-        assert_eq!(lru_cache.query(&3), Err(CacheError::CacheMiss));
+    // key 是否存在且没有过期，不会改变访问顺序
+    // 已过期但还没有被淘汰的数据视为不存在
+    pub fn contains_key(&self, key: &K) -> bool {
+        let node = match self.map.get(key).map(|index| self.list.get(index)) {
+            Some(Ok(node)) => node,
+            _ => return false,
+        };
+        self.is_live(node)
+    }
 
-        let capacity = 5;
+    #[cfg(feature = "std")]
+    fn is_live(&self, node: &Node<ListItem<K, V>>) -> bool {
+        !self.is_expired(node, self.list.now())
+    }
 
-        let mut lru_cache = Cache::<i32, i32>::new_with_cap(capacity);
-        assert_eq!(lru_cache.query(&0), Err(CacheError::CacheMiss));
+    // 没有 std 时不会超时淘汰，存在即有效
+    #[cfg(not(feature = "std"))]
+    fn is_live(&self, _node: &Node<ListItem<K, V>>) -> bool {
+        true
+    }
 
-        for ele in 0..capacity {
-            let x = ele as i32;
-            assert!(lru_cache.insert(x, x).is_ok());
+    // 已过期（达到淘汰时间或者空闲超过 max_idle）但还没有被淘汰的数据个数，不做任何修改
+    // 与 len 不同，会遍历所有数据，也不考虑宽限时间，可以用来判断是否需要调用 sweep
+    #[cfg(feature = "std")]
+    pub fn expired_count(&self) -> usize {
+        let now = self.list.now();
+        self.map
+            .values()
+            .filter_map(|index| self.list.get(index).ok())
+            .filter(|node| self.is_expired(node, now))
+            .count()
+    }
+
+    // 没有过期的数据个数，即 expired_count 的补集
+    #[cfg(feature = "std")]
+    pub fn live_count(&self) -> usize {
+        self.map.len() - self.expired_count()
+    }
+
+    // 以 now 作为当前时间，数据在逻辑上是否已经过期
+    #[cfg(feature = "std")]
+    fn is_expired(&self, node: &Node<ListItem<K, V>>, now: time::Instant) -> bool {
+        let idle = self
+            .list
+            .max_idle()
+            .is_some_and(|max_idle| now.saturating_duration_since(node.last_access()) > max_idle);
+        idle || node.expire_time().is_some_and(|expire_time| now >= expire_time)
+    }
+
+    // 与 query 相同（同样计入命中/未命中统计），但 key 不存在时返回 Ok(None)
+    // Err 只表示缓存内部结构损坏
+    pub fn query_opt(&mut self, key: &K) -> Result<Option<&V>, CacheError> {
+        match self.query(key) {
+            Ok(value) => Ok(Some(value)),
+            Err(CacheError::CacheMiss) => Ok(None),
+            Err(err) => Err(err),
         }
+    }
 
-        for ele in 0..capacity {
-            let x = ele as i32;
-            assert_eq!(lru_cache.query(&x), Ok(&x));
+    // key 不存在时先插入 default() 的结果，再对（新的或已有的）value 执行 update
+    // 该数据会被移动到头部，返回更新后的 value
+    // default 只会在 key 不存在时执行，update 总会执行
+    pub fn upsert<D, U>(&mut self, key: K, default: D, update: U) -> Result<&V, CacheError>
+    where
+        D: FnOnce() -> V,
+        U: FnOnce(&mut V),
+    {
+        self.retire()?;
+        let index = if let Some(index) = self.map.get(&key).copied() {
+            self.list.move_to_head(&index)?;
+            index
+        } else {
+            self.insert(key.clone(), default())?;
+            *self.map.get(&key).ok_or(CacheError::CacheMiss)?
+        };
+
+        let item = self.list.get_mut(&index)?;
+        update(&mut item.value.value);
+        self.reweigh(&index)?;
+        let node = self.list.get(&index)?;
+        Ok(&node.value.value)
+    }
+
+    // 按照 keys 的顺序依次 query，返回 value 的拷贝
+    // 每个命中的 key 都会被移动到头部，因此最后一个命中的 key 位于头部
+    pub fn get_many(&mut self, keys: &[K]) -> Vec<Result<V, CacheError>>
+    where
+        V: Clone,
+    {
+        keys.iter().map(|key| self.query(key).cloned()).collect()
+    }
+
+    // 按照 pairs 的顺序依次 insert，越靠后的数据越新，最后一个位于头部
+    // pairs 超过容量时靠前的数据会被淘汰；没有通过准入策略的数据会被跳过
+    // 遇到其它错误时立即返回，之前的数据已经插入
+    pub fn insert_many(&mut self, pairs: Vec<(K, V)>) -> Result<(), CacheError> {
+        for (key, value) in pairs {
+            match self.insert(key, value) {
+                Ok(_) | Err(CacheError::NotAdmitted) => {}
+                Err(e) => return Err(e),
+            }
         }
+        Ok(())
+    }
 
-        let x = capacity as i32;
-        assert!(lru_cache.insert(x, x).is_ok());
+    // 获取 key 对应的 Entry，用于原地修改或者在不存在时插入
+    // key 存在时会被移动到头部（与 touch 相同，不计入命中统计）
+    pub fn entry(&mut self, key: K) -> Result<Entry<'_, K, V>, CacheError> {
+        self.retire()?;
+        if !self.map.contains_key(&key) {
+            return Ok(Entry::Vacant(VacantEntry { cache: self, key }));
+        }
+        self.touch(&key)?;
+        let index = *self.map.get(&key).ok_or(CacheError::CacheMiss)?;
+        Ok(Entry::Occupied(OccupiedEntry {
+            cache: self,
+            key,
+            index,
+        }))
+    }
 
-        assert_eq!(lru_cache.query(&x), Ok(&x));
+    // 批量查询 keys，所有未命中的 key 只调用一次 batch_loader 批量加载并插入
+    // 按照 keys 的顺序返回 value 的拷贝，batch_loader 没有返回的 key 视为未命中（None）
+    // 没有未命中的 key 时不会调用 batch_loader
+    // 加载的 value 直接返回，即使它们没能留在缓存中（未命中的 key 多于容量、未通过准入策略等）
+    pub fn get_many_or_load<F>(
+        &mut self,
+        keys: &[K],
+        batch_loader: F,
+    ) -> Result<Vec<Option<V>>, CacheError>
+    where
+        V: Clone,
+        F: FnOnce(&[K]) -> HashMap<K, V>,
+    {
+        let (mut values, missing) = self.split_hits(keys)?;
+        if missing.is_empty() {
+            return Ok(values);
+        }
 
-        assert_eq!(lru_cache.query(&0), Err(CacheError::CacheMiss));
+        let loaded = batch_loader(&missing);
+        self.fill_loaded(keys, &missing, loaded, &mut values)?;
+        Ok(values)
+    }
 
-        let x = capacity as i32 / 2;
-        assert_eq!(lru_cache.remove(&x), Ok(x));
+    // 先执行超时淘汰，再按照 keys 的顺序查询命中的 value，同时按顺序收集（去重后的）未命中的 key
+    pub(crate) fn split_hits(&mut self, keys: &[K]) -> Result<(Vec<Option<V>>, Vec<K>), CacheError>
+    where
+        V: Clone,
+    {
+        self.retire()?;
+        let mut values = Vec::with_capacity(keys.len());
+        let mut missing: Vec<K> = vec![];
+        for key in keys {
+            let value = self.query_opt(key)?.cloned();
+            if value.is_none() && !missing.contains(key) {
+                missing.push(key.clone());
+            }
+            values.push(value);
+        }
+        Ok((values, missing))
+    }
 
-        assert_eq!(lru_cache.query(&x), Err(CacheError::CacheMiss));
-        assert_eq!(lru_cache.remove(&x), Err(CacheError::CacheMiss));
+    // 按照 missing 的顺序插入加载得到的数据，并填入 values 中对应的位置
+    // 只插入请求的 key；缓存无法容纳某个数据时只是不缓存，只有缓存本身损坏时返回错误
+    pub(crate) fn fill_loaded(
+        &mut self,
+        keys: &[K],
+        missing: &[K],
+        mut loaded: HashMap<K, V>,
+        values: &mut [Option<V>],
+    ) -> Result<(), CacheError>
+    where
+        V: Clone,
+    {
+        let mut fetched = HashMap::with_capacity(missing.len());
+        for key in missing {
+            if let Some(value) = loaded.remove(key) {
+                if let Err(e @ CacheError::CacheBroken(_)) = self.insert(key.clone(), value.clone())
+                {
+                    return Err(e);
+                }
+                fetched.insert(key, value);
+            }
+        }
+        for (key, value) in keys.iter().zip(values.iter_mut()) {
+            if value.is_none() {
+                *value = fetched.get(key).cloned();
+            }
+        }
+        Ok(())
     }
 
-    #[test]
-    fn lru_cache_timeout() {
-        let mut lru_cache = Cache::<i32, i32>::new_with_cap_timeout(5, time::Duration::from_millis(1000));
+    // key 存在时与 query 相同，将其移动到头部并返回 value
+    // 不存在时将 f() 的结果插入到头部并返回，只有未命中时才会调用 f
+    // f() 的结果没有通过准入策略时不会被缓存，但仍然会被返回
+    pub fn get_or_insert_with<F>(&mut self, key: K, f: F) -> Result<&V, CacheError>
+    where
+        F: FnOnce() -> V,
+    {
+        self.retire()?;
+        if self.map.contains_key(&key) {
+            return self.query(&key);
+        }
+        if let Err(value) = self.insert_or_reject(key.clone(), f(), None)? {
+            return Ok(self.uncached.insert(value));
+        }
+        self.peek(&key)
+    }
 
-        // [1-1]
-        lru_cache.insert(1, 1);
-        assert_eq!(lru_cache.query(&1), Ok(&1));
+    // 与 get_or_insert_with 相同，但 f 可能失败
+    // f 返回错误时不会插入任何数据（也不会淘汰数据），错误通过 CacheOrUserError::User 返回
+    pub fn get_or_try_insert_with<E, F>(&mut self, key: K, f: F) -> Result<&V, CacheOrUserError<E>>
+    where
+        F: FnOnce() -> Result<V, E>,
+    {
+        self.retire().map_err(CacheOrUserError::Cache)?;
+        if self.map.contains_key(&key) {
+            return self.query(&key).map_err(CacheOrUserError::Cache);
+        }
+        // 先计算 value，成功后才会修改缓存
+        let value = f().map_err(CacheOrUserError::User)?;
+        let inserted = self
+            .insert_or_reject(key.clone(), value, None)
+            .map_err(CacheOrUserError::Cache)?;
+        if let Err(value) = inserted {
+            return Ok(self.uncached.insert(value));
+        }
+        self.peek(&key).map_err(CacheOrUserError::Cache)
+    }
 
-        // [2-2 1-1]
-        lru_cache.insert(2, 2);
-        // [3-3 2-2 1-1]
-        lru_cache.insert(3, 3);
+    // key 存在时直接返回其 value，但不会将其移动到头部
+    // 不存在时将 f() 的结果插入到头部并返回，没有通过准入策略时与 get_or_insert_with 相同
+    pub fn get_or_insert_with_no_reposition<F>(&mut self, key: K, f: F) -> Result<&V, CacheError>
+    where
+        F: FnOnce() -> V,
+    {
+        self.retire()?;
+        if !self.map.contains_key(&key) {
+            if let Err(value) = self.insert_or_reject(key.clone(), f(), None)? {
+                return Ok(self.uncached.insert(value));
+            }
+        }
+        let index = self.map.get(&key).ok_or(CacheError::CacheMiss)?;
+        let node = self.list.get(index)?;
+        Ok(&node.value.value)
+    }
 
-        thread::sleep(time::Duration::from_millis(500));
-        assert_eq!(lru_cache.list.len(), 3);
+    #[must_use = "remove returns the removed value or CacheMiss"]
+    pub fn remove(&mut self, key: &K) -> Result<V, CacheError> {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
 
-        // [4-4 3-3 2-2 1-1]
-        lru_cache.insert(4, 4);
-        // [5-5 4-4 3-3 2-2 1-1]
-        lru_cache.insert(5, 5);
-        assert_eq!(lru_cache.list.len(), 5);
+    // 删除 key 对应的数据，同时返回缓存中存储的 key 和 value
+    pub fn remove_entry(&mut self, key: &K) -> Result<(K, V), CacheError> {
+        let index = self.map.remove(key).ok_or(CacheError::CacheMiss)?;
+        let item = self.list.remove(&index)?;
+        self.current_weight -= item.weight;
+        self.maybe_sample();
+        self.maybe_compact()?;
+        Ok((item.key, item.value))
+    }
 
-        thread::sleep(time::Duration::from_millis(500));
-        assert_eq!(lru_cache.list.len(), 5);
+    // 删除所有数据，容量、超时时间等配置以及统计信息保持不变
+    // 被删除的数据不视为淘汰，不会触发淘汰回调
+    pub fn clear(&mut self) {
+        self.list.clear();
+        self.map.clear();
+        self.current_weight = 0;
+        self.uncached = None;
+    }
 
-        // [1-1 5-5 4-4 3-3 2-2]
-        assert_eq!(lru_cache.query(&1), Ok(&1));
-        // [6-6 1-1 5-5 4-4]
-        lru_cache.insert(6, 6);
-        assert_eq!(lru_cache.list.len(), 4);
-        assert_eq!(lru_cache.query(&2), Err(CacheError::CacheMiss));
-        assert_eq!(lru_cache.query(&3), Err(CacheError::CacheMiss));
+    // 删除并返回最久未访问的数据，缓存为空时返回 None
+    // 数据的所有权交给调用方，因此不计入淘汰统计，也不会触发淘汰回调
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        let item = self.list.pop_back().ok()?;
+        self.map.remove(&item.key);
+        self.current_weight -= item.weight;
+        Some((item.key, item.value))
+    }
+
+    // 删除所有 f 返回 false 的数据，保留的数据访问顺序不变
+    // 被删除的数据不视为淘汰，不会触发淘汰回调
+    pub fn retain<F>(&mut self, mut f: F) -> Result<(), CacheError>
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        let keys: Vec<K> = self
+            .list
+            .iter()
+            .filter(|item| !f(&item.key, &item.value))
+            .map(|item| item.key.clone())
+            .collect();
+        for key in keys {
+            let index = self.map.remove(&key).ok_or(CacheError::CacheMiss)?;
+            let item = self.list.remove(&index)?;
+            self.current_weight -= item.weight;
+        }
+        self.maybe_compact()
+    }
+
+    // 按照 keys 的顺序依次删除，返回每个 key 被删除的 value，不存在时为 None
+    // keys 中重复的 key 只有第一次会返回 value
+    pub fn remove_many(&mut self, keys: &[K]) -> Vec<Option<V>> {
+        keys.iter().map(|key| self.remove(key).ok()).collect()
+    }
+
+    // 删除所有 f 返回 true 的 key，返回删除的个数，剩余数据的访问顺序不变
+    // 被删除的数据不视为淘汰，不会触发淘汰回调
+    pub fn remove_if<F>(&mut self, f: F) -> usize
+    where
+        F: Fn(&K) -> bool,
+    {
+        let keys: Vec<K> = self.map.keys().filter(|key| f(key)).cloned().collect();
+        keys.iter().filter(|key| self.remove(key).is_ok()).count()
+    }
+
+    // 取出缓存中的所有数据，按照从尾部到头部（最久未访问的在前）的顺序返回
+    // 取出的数据不视为淘汰，不会触发淘汰回调
+    // 返回的迭代器被 drop 时，尚未取出的数据会被直接删除，缓存变为空且可以继续使用
+    pub fn drain(&mut self) -> Drain<'_, K, V> {
+        Drain { cache: self }
+    }
+
+    // 与 insert 相同，但返回任何错误（缓存损坏、NotAdmitted 等）时直接 panic
+    // 适合示例代码或者确定插入不会失败的场景
+    pub fn insert_unwrap(&mut self, key: K, value: V) -> Option<V> {
+        match self.insert(key, value) {
+            Ok(old) => old,
+            Err(e) => panic!("failed to insert into cache: {e}"),
+        }
+    }
+
+    // 插入 key-value 并移动到头部
+    // key 已存在时覆盖并返回旧的 value，否则返回 None
+    #[must_use = "insert can fail (e.g. CacheBroken); handle the error or use insert_unwrap"]
+    pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>, CacheError> {
+        self.insert_with_expiry(key, value, None)
+    }
+
+    // 与 insert 相同，但该数据的存活时间为 ttl，与全局的 timeout 无关
+    // 没有设置 timeout 的缓存同样适用；ttl 过大导致溢出时视为永不过期
+    // Sliding 策略下数据被 query 移动到头部后按照自身的 ttl 重新计算淘汰时间
+    // ttl 与其它数据不一致时，之后的超时淘汰会遍历整个链表，直到淘汰时间重新有序
+    #[cfg(feature = "std")]
+    pub fn insert_with_ttl(
+        &mut self,
+        key: K,
+        value: V,
+        ttl: time::Duration,
+    ) -> Result<Option<V>, CacheError> {
+        self.insert_with_expiry(key, value, Some(ttl))
+    }
+
+    // 只在 key 不存在时插入，返回是否插入成功
+    // key 已存在时返回 Ok(false)，不会修改已有数据的 value、访问顺序和淘汰时间
+    // 与 insert 相同，插入之前会先执行超时淘汰，因此已过期并被淘汰的 key 视为不存在
+    pub fn insert_if_absent(&mut self, key: K, value: V) -> Result<bool, CacheError> {
+        self.retire()?;
+        if self.map.contains_key(&key) {
+            return Ok(false);
+        }
+        self.insert(key, value)?;
+        Ok(true)
+    }
+
+    // ttl 为 None 时按照 timeout 计算淘汰时间
+    fn insert_with_expiry(&mut self, key: K, value: V, ttl: Ttl) -> Result<Option<V>, CacheError> {
+        self.insert_or_reject(key, value, ttl)?
+            .map_err(|_| CacheError::NotAdmitted)
+    }
+
+    // 与 insert_with_expiry 相同，但没有通过准入策略时通过 Ok(Err(value)) 交还 value
+    fn insert_or_reject(
+        &mut self,
+        key: K,
+        value: V,
+        ttl: Ttl,
+    ) -> Result<Result<Option<V>, V>, CacheError> {
+        // 先检查 value 大小，避免超限的 value 淘汰其它数据
+        if let Some((max, weigher)) = &self.max_value_size {
+            let size = weigher(&value);
+            if size > *max {
+                return Err(CacheError::ValueTooLarge { size, max: *max });
+            }
+        }
+        let weight = self.weigh(&value);
+        if let Some((max, _)) = &self.max_weight {
+            if weight > *max {
+                return Err(CacheError::ValueTooLarge { size: weight, max: *max });
+            }
+        }
+
+        // 每次插入之前都进行自动淘汰（可以通过 set_auto_retire 关闭）
+        // TODO: 变为无阻塞操作
+        self.retire()?;
+        if let Some(sketch) = &mut self.admission {
+            sketch.increment(&key);
+        }
+
+        if let Some(index) = self.map.get(&key).copied() {
+            // 覆盖写入只需要容纳新旧权重的差值
+            let released = self.list.get(&index)?.value.weight;
+            self.reject_overweight(weight, released)?;
+            self.move_to_head_with_ttl(&index, ttl)?;
+            let item = self.list.get_mut(&index)?;
+            self.current_weight = self.current_weight - item.value.weight + weight;
+            item.value.weight = weight;
+            item.value.freq = item.value.freq.saturating_add(1);
+            let old = mem::replace(&mut item.value.value, value);
+            // 该数据已位于头部，只会淘汰其它数据
+            self.evict_overweight(0)?;
+            self.maybe_sample();
+            return Ok(Ok(Some(old)));
+        }
+
+        self.reject_overweight(weight, 0)?;
+        if self.frozen {
+            // 暂停淘汰期间通过扩容代替淘汰
+            if self.list.is_full() {
+                self.list.reserve(self.list.capacity().max(1));
+            }
+        } else {
+            if self.list.len() >= self.cap && !self.grow() {
+                // 没有可以淘汰的数据
+                if self.cap == 0 {
+                    return Err(CacheError::ZeroCapacity);
+                }
+                if self.growth == GrowthPolicy::Reject {
+                    return Err(CacheError::CapacityExceeded {
+                        needed: 1,
+                        available: 0,
+                    });
+                }
+                if self.admit(&key).is_err() {
+                    return Ok(Err(value));
+                }
+                self.evict_one()?;
+            }
+            if self.list.is_full() {
+                // 底层内存被 reclaim 释放或者容量扩大后，按需扩容直到 cap
+                let remaining = self.cap.saturating_sub(self.list.capacity()).max(1);
+                self.list.reserve(self.list.capacity().max(1).min(remaining));
+            }
+        }
+        self.evict_overweight(weight)?;
+
+        let item = ListItem {
+            key: key.clone(),
+            value,
+            seq: self.next_seq,
+            #[cfg(feature = "std")]
+            created_at: self.list.now(),
+            weight,
+            freq: 1,
+        };
+        let index = self.push_front_with_ttl(item, ttl)?;
+        self.map.insert(key, index);
+        self.current_weight += weight;
+        self.next_seq += 1;
+        self.maybe_sample();
+        self.maybe_compact()?;
+
+        Ok(Ok(None))
+    }
+
+    // 将已有的数据原地移动到头部，覆盖写入时总是按照 ttl（或 timeout）重新计算淘汰时间
+    #[cfg(feature = "std")]
+    fn move_to_head_with_ttl(&mut self, index: &Index, ttl: Ttl) -> Result<(), CacheError> {
+        self.list.move_to_head_with(index, false)?;
+        self.list.set_ttl(index, ttl.or(self.list.timeout()))?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn move_to_head_with_ttl(&mut self, index: &Index, _ttl: Ttl) -> Result<(), CacheError> {
+        Ok(self.list.move_to_head(index)?)
+    }
+
+    #[cfg(feature = "std")]
+    fn push_front_with_ttl(&mut self, item: ListItem<K, V>, ttl: Ttl) -> Result<Index, CacheError> {
+        let index = match ttl {
+            Some(ttl) => self.list.push_front_with_ttl(item, ttl),
+            None => self.list.push_front(item),
+        }?;
+        Ok(index)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn push_front_with_ttl(
+        &mut self,
+        item: ListItem<K, V>,
+        _ttl: Ttl,
+    ) -> Result<Index, CacheError> {
+        Ok(self.list.push_front(item)?)
+    }
+
+    // 批量插入，key 重复时（已在缓存中或在 iter 中重复出现）
+    // 通过 resolve(旧值, 新值) 合并，并将该数据移动到头部
+    // 遇到第一个错误时停止插入
+    pub fn extend_with<I, F>(&mut self, iter: I, mut resolve: F) -> Result<(), CacheError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        F: FnMut(&mut V, V),
+    {
+        for (key, value) in iter {
+            self.retire()?;
+            if let Some(index) = self.map.get(&key).copied() {
+                self.list.move_to_head(&index)?;
+                let item = self.list.get_mut(&index)?;
+                resolve(&mut item.value.value, value);
+                self.reweigh(&index)?;
+            } else {
+                self.insert(key, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    // 将 keys 中存在的 key 的淘汰时间统一设置为绝对时间 deadline
+    // 返回实际更新的 key 的个数
+    // deadline 与其它节点不一致时，之后的超时淘汰会遍历整个链表，位于链表中间的节点同样会按时淘汰
+    // 之后的 query、touch 不会延长 deadline，再次 insert 时才会重新计算淘汰时间
+    #[cfg(feature = "std")]
+    pub fn set_expiry_many(&mut self, keys: &[K], deadline: time::Instant) -> usize {
+        let mut updated = 0;
+        for key in keys {
+            if let Some(index) = self.map.get(key) {
+                if self.list.set_expire_time(index, deadline).is_ok() {
+                    updated += 1;
+                }
+            }
+        }
+        updated
+    }
+
+    // 按照从 LRU 到 MRU 的顺序导出所有数据及其时间信息
+    // 通过 import_ordered 可以在另一个缓存中重建相同的访问顺序和存活时间
+    #[cfg(feature = "std")]
+    pub fn export_ordered(&self) -> Vec<ExportEntry<K, V>>
+    where
+        V: Clone,
+    {
+        let now = self.list.now();
+        let mut entries = Vec::with_capacity(self.list.len());
+        for item in self.list.iter() {
+            let index = self.map.get(&item.key);
+            let node = match index.and_then(|index| self.list.get(index).ok()) {
+                Some(node) => node,
+                None => continue,
+            };
+            entries.push(ExportEntry {
+                key: item.key.clone(),
+                value: item.value.clone(),
+                created_at: item.created_at,
+                last_accessed: node.last_access(),
+                ttl: node
+                    .expire_time()
+                    .map(|expire_time| expire_time.saturating_duration_since(now)),
+            });
+        }
+        entries.reverse();
+        entries
+    }
+
+    // 按顺序导入 export_ordered 导出的数据，最后导入的数据位于头部
+    // 导入的数据保留原有的时间信息，超出容量时按照正常的淘汰规则淘汰
+    #[cfg(feature = "std")]
+    pub fn import_ordered<I>(&mut self, entries: I) -> Result<(), CacheError>
+    where
+        I: IntoIterator<Item = ExportEntry<K, V>>,
+    {
+        let now = self.list.now();
+        for entry in entries {
+            self.insert(entry.key.clone(), entry.value)?;
+            let index = *self.map.get(&entry.key).ok_or(CacheError::CacheMiss)?;
+            match entry.ttl {
+                Some(ttl) => self.list.set_expire_time(&index, now + ttl),
+                None => self.list.clear_expire_time(&index),
+            }?;
+            self.list.set_last_access(&index, entry.last_accessed)?;
+            let item = self.list.get_mut(&index)?;
+            item.value.created_at = entry.created_at;
+        }
+        Ok(())
+    }
+
+    // 从只保存了顺序的持久化数据中恢复缓存，pairs 按照从 LRU 到 MRU 的顺序排列
+    // 恢复后的淘汰顺序由插入顺序决定，与 pairs 的顺序一致
+    // 为了不让 idle_time、max_idle 等看到相同的访问时间，按照顺序将访问时间均匀分布在
+    // [now - spread, now] 之间：第一个最早，最后一个为 now
+    // spread 过大导致溢出时访问时间截断为 now - spread，早于 Instant 的下限时截断为 now
+    #[cfg(feature = "std")]
+    pub fn restore_ordered<I>(
+        &mut self,
+        pairs: I,
+        spread: time::Duration,
+    ) -> Result<(), CacheError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let pairs: Vec<(K, V)> = pairs.into_iter().collect();
+        let now = self.list.now();
+        let last = pairs.len().saturating_sub(1);
+        let steps = u32::try_from(last).unwrap_or(u32::MAX).max(1);
+        for (i, (key, value)) in pairs.into_iter().enumerate() {
+            self.insert(key.clone(), value)?;
+            let offset = u32::try_from(last - i)
+                .ok()
+                .and_then(|remaining| spread.checked_mul(remaining))
+                .map_or(spread, |total| total / steps);
+            let last_access = now.checked_sub(offset).unwrap_or(now);
+            let index = *self.map.get(&key).ok_or(CacheError::CacheMiss)?;
+            self.list.set_last_access(&index, last_access)?;
+        }
+        Ok(())
+    }
+
+    // 按照最近访问的先后顺序遍历缓存（最近访问的在前），不会改变访问顺序
+    // 已超时但还没有被淘汰的数据同样会被遍历到（下一次写入时才会被淘汰）
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.list.iter().map(|item| (&item.key, &item.value))
+    }
+
+    // 与 iter 相反，从最久未访问的数据开始遍历，即下一次按容量淘汰的顺序
+    pub fn iter_lru_first(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.list.iter_rev().map(|item| (&item.key, &item.value))
+    }
+
+    // 按照最近访问的先后顺序遍历缓存中的 key
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.list.iter().map(|item| &item.key)
+    }
+
+    // 按照最近访问的先后顺序遍历缓存中的 value
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.list.iter().map(|item| &item.value)
+    }
+
+    // 按照最近访问的先后顺序获取缓存中 value 的可变引用，不会改变访问顺序和淘汰时间
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.list.iter_mut().map(|item| &mut item.value)
+    }
+
+    // 按照插入的先后顺序遍历缓存（最早插入的在前），不受访问顺序影响
+    pub fn iter_by_insertion(&self) -> impl Iterator<Item = (&K, &V)> {
+        let mut items: Vec<&ListItem<K, V>> = self.list.iter().collect();
+        items.sort_by_key(|item| item.seq);
+        items.into_iter().map(|item| (&item.key, &item.value))
+    }
+
+    // 最近 window 时间内被插入或访问过的数据个数，即近期的工作集大小
+    // 从头部开始统计，遇到第一个更早访问的数据时停止
+    // 通过 get_or_insert_with_no_reposition 访问的数据不会更新访问时间
+    #[cfg(feature = "std")]
+    pub fn entries_accessed_within(&self, window: time::Duration) -> usize {
+        match self.list.now().checked_sub(window) {
+            Some(since) => self.list.count_accessed_since(since),
+            None => self.list.len(),
+        }
+    }
+
+    // 底层 Arena 的代数与 u64::MAX 的接近程度，取值范围 [0, 1]
+    // 接近 1 时说明代数即将耗尽（耗尽后插入会返回 ListOOM），应当考虑重建缓存
+    pub fn generation_pressure(&self) -> f32 {
+        (self.list.max_generation() as f64 / u64::MAX as f64) as f32
+    }
+
+    // 缓存中未过期的数据个数
+    // 已过期但还没有被淘汰的数据不计入，即与下一次超时淘汰（sweep）之后的个数一致
+    // 淘汰时间被打乱（Fixed 策略、单独设置的淘汰时间等）时与 sweep 相同会遍历所有数据
+    pub fn len(&self) -> usize {
+        if self.frozen {
+            // 暂停淘汰期间过期数据不会被淘汰
+            return self.list.len();
+        }
+        self.list.len() - self.retirable_count()
+    }
+
+    #[cfg(feature = "std")]
+    fn retirable_count(&self) -> usize {
+        self.list.count_retirable_at(self.list.now())
+    }
+
+    // 没有 std 时不会超时淘汰
+    #[cfg(not(feature = "std"))]
+    fn retirable_count(&self) -> usize {
+        0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // 缓存最多可以容纳的数据个数
+    // 暂停淘汰期间底层链表可能扩容，但缓存的容量不变
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    // 开始记录 query 的 key 序列，最多保留最近的 size 个
+    pub fn start_recording(&mut self, size: usize) {
+        self.access_log = Some(VecDeque::with_capacity(size));
+        self.access_log_size = size;
+    }
+
+    // 停止记录并返回已记录的 key 序列
+    pub fn stop_recording(&mut self) -> Vec<K> {
+        self.access_log.take().map(Vec::from).unwrap_or_default()
+    }
+
+    // 返回目前已记录的 key 序列（从旧到新）
+    pub fn access_pattern(&self) -> Vec<K> {
+        self.access_log
+            .as_ref()
+            .map(|log| log.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    // 按照 pattern 的顺序重放访问：已存在的 key 会被移动到头部，
+    // 不存在的 key 通过 loader 加载后插入，loader 返回 None 的 key 会被跳过
+    // 返回通过 loader 加载的数据个数
+    pub fn warm<F>(&mut self, pattern: &[K], mut loader: F) -> Result<usize, CacheError>
+    where
+        F: FnMut(&K) -> Option<V>,
+    {
+        let mut loaded = 0;
+        for key in pattern {
+            if self.map.contains_key(key) {
+                self.query(key)?;
+            } else if let Some(value) = loader(key) {
+                self.insert(key.clone(), value)?;
+                loaded += 1;
+            }
+        }
+        Ok(loaded)
+    }
+
+    // 按照给定的顺序批量插入数据，第一个数据位于尾部（LRU），最后一个位于头部（MRU）
+    // 插入期间暂停自动超时淘汰和准入策略，超过容量时与 insert 相同按照 LRU 淘汰，
+    // 即只保留最后 capacity 个数据
+    pub fn warm_up<I>(&mut self, pairs: I) -> Result<(), CacheError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let auto_retire = mem::replace(&mut self.auto_retire, false);
+        let admission = self.admission.take();
+        let result = pairs
+            .into_iter()
+            .try_for_each(|(key, value)| self.insert(key, value).map(|_| ()));
+        self.auto_retire = auto_retire;
+        self.admission = admission;
+        result
+    }
+
+    fn record_access(&mut self, key: &K) {
+        if let Some(log) = &mut self.access_log {
+            if self.access_log_size == 0 {
+                return;
+            }
+            while log.len() >= self.access_log_size {
+                log.pop_front();
+            }
+            log.push_back(key.clone());
+        }
+    }
+
+    // 记录一次当前时间和数据个数
+    // 超过 sample_ring_size 时丢弃最旧的采样
+    #[cfg(feature = "std")]
+    pub fn sample(&mut self) {
+        if self.sample_ring_size == 0 {
+            return;
+        }
+        while self.samples.len() >= self.sample_ring_size {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((self.list.now(), self.list.len()));
+    }
+
+    // 按照采样时间从旧到新返回保留的采样
+    #[cfg(feature = "std")]
+    pub fn utilization_samples(&self) -> impl Iterator<Item = (time::Instant, usize)> + '_ {
+        self.samples.iter().copied()
+    }
+
+    // 设置最多保留的采样个数
+    #[cfg(feature = "std")]
+    pub fn set_sample_ring_size(&mut self, size: usize) {
+        self.sample_ring_size = size;
+        while self.samples.len() > size {
+            self.samples.pop_front();
+        }
+    }
+
+    // 设置自动采样间隔，距上次采样超过 interval 后在 insert、query、remove 等操作时自动采样
+    #[cfg(feature = "std")]
+    pub fn set_sample_interval(&mut self, interval: Option<time::Duration>) {
+        self.sample_interval = interval;
+    }
+
+    #[cfg(feature = "std")]
+    fn maybe_sample(&mut self) {
+        if let Some(interval) = self.sample_interval {
+            let due = match self.samples.back() {
+                Some((last, _)) => self.list.now().saturating_duration_since(*last) >= interval,
+                None => true,
+            };
+            if due {
+                self.sample();
+            }
+        }
+    }
+
+    // 没有 std 时无法记录采样时间，不进行采样
+    #[cfg(not(feature = "std"))]
+    fn maybe_sample(&mut self) {}
+
+    // 暂停容量淘汰和超时淘汰，直到返回的 guard 被 drop
+    // 暂停期间插入新数据会使链表扩容而不是淘汰旧数据，因此内存会持续增长
+    // guard 被 drop 后会先执行超时淘汰，再从尾部淘汰超出容量的数据
+    // 注意：链表扩容得到的内存不会被释放
+    pub fn freeze_eviction(&mut self) -> EvictionGuard<'_, K, V> {
+        self.frozen = true;
+        EvictionGuard { cache: self }
+    }
+
+    // 恢复淘汰，并执行暂停期间被推迟的淘汰
+    fn unfreeze_eviction(&mut self) -> Result<(), CacheError> {
+        self.frozen = false;
+        self.retire()?;
+        while self.list.len() > self.cap {
+            self.evict_one()?;
+        }
+        self.evict_overweight(0)
+    }
+
+    // 开启准入策略时，key 的估计访问频率不高于链表尾部的数据则拒绝插入
+    fn admit(&self, key: &K) -> Result<(), CacheError> {
+        let (sketch, victim) = match (&self.admission, self.victim()) {
+            (Some(sketch), Some(victim)) => (sketch, victim),
+            _ => return Ok(()),
+        };
+        if sketch.estimate(key) > sketch.estimate(&victim.key) {
+            Ok(())
+        } else {
+            Err(CacheError::NotAdmitted)
+        }
+    }
+
+    // Grow 策略下将容量扩大一倍（不超过 max），返回是否扩容成功
+    fn grow(&mut self) -> bool {
+        let max = match self.growth {
+            GrowthPolicy::EvictLru | GrowthPolicy::Reject => return false,
+            GrowthPolicy::Grow { max } => max.unwrap_or(usize::MAX),
+        };
+        let new_cap = self.cap.saturating_mul(2).max(1).min(max);
+        if new_cap <= self.cap {
+            return false;
+        }
+        self.cap = new_cap;
+        true
+    }
+
+    // 下一个会被容量淘汰的数据：LRU 策略下为链表尾部的数据，
+    // LFU 策略下为访问次数最少的数据中最久未访问的一个
+    fn victim(&self) -> Option<&ListItem<K, V>> {
+        match self.eviction {
+            EvictionPolicy::Lru => self.list.peek_back().ok(),
+            // 从尾部开始遍历，次数相同时 min_by_key 返回第一个，即最久未访问的
+            // 头部是刚写入或访问的数据，只有它是唯一的数据时才会被淘汰
+            EvictionPolicy::Lfu => {
+                let candidates = self.list.len().saturating_sub(1).max(1);
+                self.list
+                    .iter_rev()
+                    .take(candidates)
+                    .min_by_key(|item| item.freq)
+            }
+        }
+    }
+
+    // 按照淘汰策略淘汰一个数据
+    fn evict_one(&mut self) -> Result<(), CacheError> {
+        let index = self
+            .victim()
+            .and_then(|item| self.map.get(&item.key))
+            .copied();
+        let item = match index {
+            Some(index) => self.list.remove(&index)?,
+            // 缓存为空，由 pop_back 返回对应的错误
+            None => self.list.pop_back()?,
+        };
+        self.map.remove(&item.key);
+        self.current_weight -= item.weight;
+        self.evicted += 1;
+        self.notify_evicted(item.key, item.value, EvictionReason::Capacity);
+        Ok(())
+    }
+
+    // 查询的命中统计，只有 query 和 count_stats 为 true 的 query_opts 会计入统计
+    // peek、contains_key 等只读方法不计入统计
+    pub fn stats(&self) -> CacheStats {
+        let total = self.hits + self.misses;
+        let hit_rate = if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        };
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            hit_rate,
+        }
+    }
+
+    // 清空命中统计
+    pub fn reset_stats(&mut self) {
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    // 自创建以来因容量已满被淘汰的数据总数
+    pub fn total_evicted(&self) -> u64 {
+        self.evicted
+    }
+
+    // 自创建以来因超时被淘汰的数据总数
+    pub fn total_expired(&self) -> u64 {
+        self.expired
+    }
+
+    // 订阅淘汰事件，被淘汰（容量淘汰或超时淘汰）的数据会按淘汰顺序发送到返回的接收端
+    // 重复调用会替换之前的订阅，旧的接收端不会再收到事件
+    // 通道没有上限，接收端不及时处理时未处理的事件会持续占用内存
+    // 可以与 on_evict 同时使用，此时回调获得 value 的所有权，通道收到 value 的拷贝
+    #[cfg(feature = "std")]
+    pub fn eviction_events(&mut self) -> mpsc::Receiver<(K, V, EvictionReason)>
+    where
+        V: Clone,
+    {
+        let (tx, rx) = mpsc::channel();
+        self.eviction_tx = Some(EvictionSubscriber {
+            tx,
+            clone_value: V::clone,
+        });
+        rx
+    }
+
+    // 注册淘汰回调，容量淘汰和超时淘汰的数据会按淘汰顺序交给回调处理
+    // 回调在 map 和 list 更新完成后才被调用
+    // 重复调用会替换之前的回调，不影响 eviction_events 的订阅
+    pub fn on_evict(&mut self, f: impl FnMut(K, V) + Send + 'static) -> &mut Self {
+        self.on_evict = Some(Box::new(f));
+        self
+    }
+
+    #[cfg(feature = "std")]
+    fn notify_evicted(&mut self, key: K, value: V, reason: EvictionReason) {
+        if self.on_evict.is_none() {
+            self.send_evicted(key, value, reason);
+            return;
+        }
+        if let Some(subscriber) = &self.eviction_tx {
+            let copy = (subscriber.clone_value)(&value);
+            self.send_evicted(key.clone(), copy, reason);
+        }
+        if let Some(f) = &mut self.on_evict {
+            f(key, value);
+        }
+    }
+
+    // 没有 std 时不支持订阅淘汰事件，只调用淘汰回调
+    #[cfg(not(feature = "std"))]
+    fn notify_evicted(&mut self, key: K, value: V, _reason: EvictionReason) {
+        if let Some(f) = &mut self.on_evict {
+            f(key, value);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn send_evicted(&mut self, key: K, value: V, reason: EvictionReason) {
+        if let Some(subscriber) = &self.eviction_tx {
+            if subscriber.tx.send((key, value, reason)).is_err() {
+                // 接收端已被 drop，取消订阅
+                self.eviction_tx = None;
+            }
+        }
+    }
+
+    // 调整缓存的容量，不重建缓存，数据的访问顺序和淘汰时间保持不变
+    // 扩大容量时底层链表同步扩容
+    // 缩小容量时从尾部淘汰数据直到数据个数不超过 new_cap（按容量淘汰处理）
+    // 底层 Arena 只能扩容，缩小容量后多余的内存不会被释放，只是不再使用
+    // 暂停淘汰期间缩小容量时，淘汰会推迟到恢复淘汰时执行
+    pub fn resize(&mut self, new_cap: usize) -> Result<(), CacheError> {
+        if new_cap > self.list.capacity() {
+            self.list.reserve(new_cap - self.list.capacity());
+        }
+        self.cap = new_cap;
+        if self.frozen {
+            return Ok(());
+        }
+        while self.list.len() > self.cap {
+            self.evict_one()?;
+        }
+        Ok(())
+    }
+
+    // 将缓存的容量扩大 additional 个，并立即申请底层内存
+    pub fn reserve(&mut self, additional: usize) {
+        let new_cap = self.cap.saturating_add(additional);
+        if new_cap > self.list.capacity() {
+            self.list.reserve(new_cap - self.list.capacity());
+        }
+        self.cap = new_cap;
+    }
+
+    // 与 reserve 相同
+    // 但内存申请失败时返回 ListOOM 而不是终止进程，此时缓存保持不变
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), CacheError> {
+        let oom = CacheError::from(ArenaOOM {});
+        let new_cap = self.cap.checked_add(additional).ok_or(oom)?;
+        if new_cap > self.list.capacity() {
+            self.list.try_reserve(new_cap - self.list.capacity())?;
+        }
+        self.cap = new_cap;
+        Ok(())
+    }
+
+    // 释放底层内存中所有空闲的内存单位，缓存的容量、数据和访问顺序保持不变
+    // 之后插入新数据时底层内存会按需扩容，直到达到缓存的容量
+    pub fn reclaim(&mut self) -> Result<(), CacheError> {
+        let remap = self.list.reclaim()?;
+        self.apply_remap(remap)
+    }
+
+    // 与 reclaim 相同，并将底层内存和 map 中多余的内存归还给分配器
+    // 所有数据和访问顺序保持不变
+    pub fn shrink_to_fit(&mut self) -> Result<(), CacheError> {
+        let remap = self.list.shrink_to_fit()?;
+        self.apply_remap(remap)?;
+        self.map.shrink_to_fit();
+        Ok(())
+    }
+
+    // 根据底层内存整理后的 (旧 Index, 新 Index) 更新 map
+    fn apply_remap(&mut self, remap: Vec<(Index, Index)>) -> Result<(), CacheError> {
+        let lookup: HashMap<Index, Index> = remap.into_iter().collect();
+        for index in self.map.values_mut() {
+            *index = *lookup
+                .get(index)
+                .ok_or_else(|| ListError::link_broken(index))?;
+        }
+        Ok(())
+    }
+
+    // 以新的容量和淘汰策略重建缓存，例如在线从 LRU 切换为 LFU
+    // 按照新的淘汰策略保留 new_cap 个数据（LRU 保留最近访问的，LFU 保留访问次数最多的），
+    // 其余数据按容量淘汰处理
+    // 保留的数据的访问顺序、访问次数、最近访问时间和淘汰时间（包括单独设置的淘汰时间）都保持不变
+    // 底层内存会被整理为 new_cap 的大小，之前的 Index 全部失效
+    pub fn reconfigure(
+        &mut self,
+        new_cap: usize,
+        policy: EvictionPolicy,
+    ) -> Result<(), CacheError> {
+        self.eviction = policy;
+        while self.list.len() > new_cap {
+            self.evict_one()?;
+        }
+        let remap = self.list.shrink_to_fit()?;
+        self.apply_remap(remap)?;
+        self.list.reserve(new_cap - self.list.len());
+        self.cap = new_cap;
+        Ok(())
+    }
+
+    // 当前的淘汰策略
+    pub fn eviction_policy(&self) -> EvictionPolicy {
+        self.eviction
+    }
+
+    // 转换为容量为 new_cap 的 LfuCache，用于在线将淘汰策略从 LRU 切换为 LFU
+    // 与 reconfigure 切换为 LFU 相同，保留访问次数最多的 new_cap 个数据，其余数据按容量淘汰处理
+    // 保留的数据沿用原有的访问次数，并按照从旧到新的顺序插入，因此访问次数相同时仍然先淘汰最久未访问的数据
+    // LfuCache 没有超时淘汰和权重，这些设置不会保留
+    pub fn into_lfu(mut self, new_cap: usize) -> Result<LfuCache<K, V>, CacheError> {
+        self.eviction = EvictionPolicy::Lfu;
+        while self.list.len() > new_cap {
+            self.evict_one()?;
+        }
+        let mut lfu_cache = LfuCache::new_with_cap(new_cap);
+        while let Ok(item) = self.list.pop_back() {
+            self.map.remove(&item.key);
+            lfu_cache.insert_with_frequency(item.key, item.value, item.freq)?;
+        }
+        Ok(lfu_cache)
+    }
+
+    // 底层内存空闲区域的碎片程度，取值范围 [0, 1]
+    pub fn fragmentation(&self) -> f32 {
+        self.list.fragmentation()
+    }
+
+    // 碎片程度超过 threshold 后，在 insert / remove 之后自动整理内存
+    // 计算碎片程度需要遍历空闲区域，开启后每次操作都会有额外开销
+    pub fn set_auto_compact_threshold(&mut self, threshold: Option<f32>) {
+        self.auto_compact_threshold = threshold;
+    }
+
+    // 按照访问顺序整理底层内存，并重建 map 中的 index
+    pub fn compact(&mut self) -> Result<(), CacheError> {
+        let indices = self.list.compact()?;
+        for (item, index) in self.list.iter().zip(indices) {
+            self.map.insert(item.key.clone(), index);
+        }
+        Ok(())
+    }
+
+    fn maybe_compact(&mut self) -> Result<(), CacheError> {
+        if let Some(threshold) = self.auto_compact_threshold {
+            if self.fragmentation() > threshold {
+                self.compact()?;
+            }
+        }
+        Ok(())
+    }
+
+    // 将缓存的时钟设置为 instant 并执行超时淘汰，返回被淘汰的个数
+    // 之后的 query、ttl、insert 等操作都以 instant 作为当前时间，可用于测试或推演某一时刻的缓存状态
+    // 时钟不支持设置时（例如默认的 SystemClock）不会修改时钟，只是将 instant 之前到期的数据提前淘汰
+    #[cfg(feature = "std")]
+    pub fn advance_to(&mut self, instant: time::Instant) -> Result<usize, CacheError> {
+        self.list.clock().set_now(instant);
+        self.retire_at(instant)
+    }
+
+    // 是否在 insert 等写入操作之前自动执行超时淘汰，默认开启
+    // 关闭后过期数据会一直保留（query 仍然可以读到），直到调用 sweep 等方法显式淘汰
+    pub fn set_auto_retire(&mut self, enabled: bool) {
+        self.auto_retire = enabled;
+    }
+
+    // 写入操作之前的自动超时淘汰
+    #[cfg(feature = "std")]
+    fn retire(&mut self) -> Result<(), CacheError> {
+        if !self.auto_retire {
+            return Ok(());
+        }
+        self.retire_at(self.list.now())?;
+        Ok(())
+    }
+
+    // 没有 std 时不会超时淘汰
+    #[cfg(not(feature = "std"))]
+    fn retire(&mut self) -> Result<(), CacheError> {
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    fn retire_at(&mut self, now: time::Instant) -> Result<usize, CacheError> {
+        if self.frozen {
+            return Ok(0);
+        }
+        let retired_items = self.list.retire_at(now)?;
+        self.expire_items(retired_items)
+    }
+
+    // 立即执行一次超时淘汰，返回被淘汰的个数
+    // 超时淘汰默认只在 insert 时进行，读多写少时可以定期调用 sweep 及时释放过期数据
+    // 与 insert 时相同，通常只从链表尾部开始淘汰；Fixed 策略、单独设置的淘汰时间等
+    // 使过期数据位于链表中间时会遍历整个链表
+    #[cfg(feature = "std")]
+    pub fn sweep(&mut self) -> Result<usize, CacheError> {
+        self.retire_at(self.list.now())
+    }
+
+    // 遍历整个缓存执行超时淘汰，返回被淘汰的个数
+    // 与每次 insert 时只从链表尾部开始的超时淘汰不同，可以淘汰位于链表中间的过期数据
+    // 需要遍历所有数据，即使淘汰时间看起来有序也会检查每个数据
+    #[cfg(feature = "std")]
+    pub fn retire_all(&mut self) -> Result<usize, CacheError> {
+        if self.frozen {
+            return Ok(0);
+        }
+        let retired_items = self.list.retire_all()?;
+        self.expire_items(retired_items)
+    }
+
+    // 同步 map 并通知被超时淘汰的数据
+    #[cfg(feature = "std")]
+    fn expire_items(&mut self, items: Option<Vec<ListItem<K, V>>>) -> Result<usize, CacheError> {
+        let items = match items {
+            Some(items) => items,
+            None => return Ok(0),
+        };
+        let count = items.len();
+        for item in &items {
+            self.map.remove(&item.key).ok_or(CacheError::CacheMiss)?;
+            self.current_weight -= item.weight;
+        }
+        self.expired += count as u64;
+        for item in items {
+            self.notify_evicted(item.key, item.value, EvictionReason::Expired);
+        }
+        Ok(count)
+    }
+}
+
+// 与 Cache::new 相同
+impl<K, V> Default for Cache<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 容量为输入数据的个数，不带有超时淘汰机制
+// 按照输入的顺序插入，最后一个数据位于头部；key 重复时保留最后一次插入的 value
+impl<K, V> FromIterator<(K, V)> for Cache<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let pairs: Vec<(K, V)> = iter.into_iter().collect();
+        let mut cache = Self::new_with_cap(pairs.len());
+        for (key, value) in pairs {
+            // 容量不小于数据个数且没有超时、权重和准入限制，插入不会失败
+            cache
+                .insert(key, value)
+                .expect("cache sized to the input rejected an insert");
+        }
+        cache
+    }
+}
+
+// 缓存中某个 key 的 Entry，由 Cache::entry 返回
+pub enum Entry<'a, K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+// 已存在的 key，创建时已经被移动到头部
+pub struct OccupiedEntry<'a, K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    cache: &'a mut Cache<K, V>,
+    key: K,
+    index: Index,
+}
+
+// 不存在的 key
+pub struct VacantEntry<'a, K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    cache: &'a mut Cache<K, V>,
+    key: K,
+}
+
+impl<'a, K, V> Entry<'a, K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    // key 不存在时插入 default，返回 value 的可变引用
+    pub fn or_insert(self, default: V) -> Result<&'a mut V, CacheError> {
+        self.or_insert_with(|| default)
+    }
+
+    // key 不存在时插入 f() 的结果，只有不存在时才会调用 f
+    pub fn or_insert_with<F>(self, f: F) -> Result<&'a mut V, CacheError>
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(f()),
+        }
+    }
+
+    // key 存在时对其 value 执行 f
+    pub fn and_modify<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        if let Entry::Occupied(entry) = &mut self {
+            if let Ok(value) = entry.get_mut() {
+                f(value);
+            }
+        }
+        self
+    }
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn get(&self) -> Result<&V, CacheError> {
+        let node = self.cache.list.get(&self.index)?;
+        Ok(&node.value.value)
+    }
+
+    pub fn get_mut(&mut self) -> Result<&mut V, CacheError> {
+        let node = self.cache.list.get_mut(&self.index)?;
+        Ok(&mut node.value.value)
+    }
+
+    // 与 get_mut 相同，但返回的引用与缓存的生命周期相同
+    pub fn into_mut(self) -> Result<&'a mut V, CacheError> {
+        let node = self.cache.list.get_mut(&self.index)?;
+        Ok(&mut node.value.value)
+    }
+
+    // 删除该数据并返回其 value
+    pub fn remove(self) -> Result<V, CacheError> {
+        self.cache.remove(&self.key)
+    }
+}
+
+impl<'a, K, V> VacantEntry<'a, K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    // 与 Cache::insert 相同，插入到头部并按照容量淘汰，返回 value 的可变引用
+    // 没有通过准入策略时不会被缓存，返回的引用指向未缓存的 value，修改它不会影响缓存
+    pub fn insert(self, value: V) -> Result<&'a mut V, CacheError> {
+        if let Err(value) = self.cache.insert_or_reject(self.key.clone(), value, None)? {
+            return Ok(self.cache.uncached.insert(value));
+        }
+        let index = self.cache.map.get(&self.key).ok_or(CacheError::CacheMiss)?;
+        let node = self.cache.list.get_mut(index)?;
+        Ok(&mut node.value.value)
+    }
+}
+
+// cache[&key] 与 peek 相同，不会改变访问顺序，也不计入命中统计
+// key 不存在时 panic
+impl<K, V> ops::Index<&K> for Cache<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    type Output = V;
+
+    fn index(&self, key: &K) -> &V {
+        self.peek(key).expect("key not found in cache")
+    }
+}
+
+// 与 Index 相同，可以原地修改 value，但不会改变访问顺序
+impl<K, V> ops::IndexMut<&K> for Cache<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    fn index_mut(&mut self, key: &K) -> &mut V {
+        let node = self
+            .map
+            .get(key)
+            .and_then(|index| self.list.get_mut(index).ok())
+            .expect("key not found in cache");
+        &mut node.value.value
+    }
+}
+
+// 依次通过 insert 插入，容量淘汰和超时淘汰照常进行，最后一个数据位于头部
+// Extend 无法返回错误，insert 返回的错误都会被忽略：
+// ValueTooLarge（value 超过大小限制）、NotAdmitted（没有通过准入策略）和
+// CapacityExceeded（Reject 策略下缓存已满）的数据会被跳过，之后的数据继续插入；
+// 遇到 ZeroCapacity（缓存容量为 0）或 CacheBroken（包括内存申请失败）时停止插入，剩余的数据被丢弃
+impl<K, V> Extend<(K, V)> for Cache<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            if let Err(CacheError::ZeroCapacity | CacheError::CacheBroken(_)) =
+                self.insert(key, value)
+            {
+                break;
+            }
+        }
+    }
+}
+
+// 复制出一个独立的缓存，访问顺序、淘汰时间和统计信息与原缓存相同
+// 底层 Arena 按原样复制，map 中的 Index 在新缓存中仍然有效
+// 淘汰回调和淘汰事件的订阅不会被复制；新缓存不处于暂停淘汰的状态
+impl<K, V> Clone for Cache<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        Cache {
+            list: self.list.clone(),
+            map: self.map.clone(),
+            cap: self.cap,
+            frozen: false,
+            max_value_size: self.max_value_size.clone(),
+            max_weight: self.max_weight.clone(),
+            current_weight: self.current_weight,
+            next_seq: self.next_seq,
+            #[cfg(feature = "std")]
+            samples: self.samples.clone(),
+            #[cfg(feature = "std")]
+            sample_ring_size: self.sample_ring_size,
+            #[cfg(feature = "std")]
+            sample_interval: self.sample_interval,
+            access_log: self.access_log.clone(),
+            access_log_size: self.access_log_size,
+            #[cfg(feature = "std")]
+            eviction_tx: None,
+            on_evict: None,
+            hits: self.hits,
+            misses: self.misses,
+            auto_compact_threshold: self.auto_compact_threshold,
+            auto_retire: self.auto_retire,
+            growth: self.growth,
+            eviction: self.eviction,
+            admission: self.admission.clone(),
+            uncached: None,
+            evicted: self.evicted,
+            expired: self.expired,
+        }
+    }
+}
+
+// 按照最近访问的先后顺序输出缓存中的数据，不输出内部的 Index
+impl<K, V> fmt::Debug for Cache<K, V>
+where
+    K: Eq + Hash + Clone + fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("Cache");
+        debug.field("cap", &self.cap);
+        #[cfg(feature = "std")]
+        debug.field("timeout", &self.list.timeout());
+        debug.field("entries", &Entries(&self.list)).finish()
+    }
+}
+
+struct Entries<'a, K, V>(&'a LinkedList<ListItem<K, V>>);
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Entries<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.0.iter().map(|item| (&item.key, &item.value)))
+            .finish()
+    }
+}
+
+// 序列化时按照最近访问的先后顺序（MRU 在前）保存数据、容量和 timeout
+// Instant 无法序列化，因此每个数据保存的是序列化时剩余的存活时间，反序列化时以当前时间重新计算
+// 序列化时已过期的数据在反序列化时会被丢弃
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::*;
+
+    #[derive(Serialize)]
+    struct SnapshotRef<'a, K, V> {
+        cap: usize,
+        timeout: Option<time::Duration>,
+        entries: Vec<(&'a K, &'a V, Option<time::Duration>)>,
+    }
+
+    #[derive(Deserialize)]
+    struct Snapshot<K, V> {
+        cap: usize,
+        timeout: Option<time::Duration>,
+        entries: Vec<(K, V, Option<time::Duration>)>,
+    }
+
+    impl<K, V> Serialize for Cache<K, V>
+    where
+        K: Eq + Hash + Clone + Serialize,
+        V: Serialize,
+    {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let now = self.list.now();
+            let mut entries = Vec::with_capacity(self.list.len());
+            for item in self.list.iter() {
+                let node = self
+                    .map
+                    .get(&item.key)
+                    .and_then(|index| self.list.get(index).ok())
+                    .ok_or_else(|| serde::ser::Error::custom(CacheError::CacheMiss))?;
+                let ttl = node
+                    .expire_time()
+                    .map(|expire_time| expire_time.saturating_duration_since(now));
+                entries.push((&item.key, &item.value, ttl));
+            }
+            SnapshotRef {
+                cap: self.cap,
+                timeout: self.list.timeout(),
+                entries,
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de, K, V> Deserialize<'de> for Cache<K, V>
+    where
+        K: Eq + Hash + Clone + Deserialize<'de>,
+        V: Deserialize<'de>,
+    {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let snapshot = Snapshot::<K, V>::deserialize(deserializer)?;
+            let mut cache = match snapshot.timeout {
+                Some(timeout) => Cache::new_with_cap_timeout(snapshot.cap, timeout),
+                None => Cache::new_with_cap(snapshot.cap),
+            };
+            let now = cache.list.now();
+            // 从 LRU 开始插入，使最后插入的 MRU 位于头部
+            let entries = snapshot
+                .entries
+                .into_iter()
+                .rev()
+                .filter(|(_, _, ttl)| *ttl != Some(time::Duration::ZERO))
+                .map(|(key, value, ttl)| ExportEntry {
+                    key,
+                    value,
+                    created_at: now,
+                    last_accessed: now,
+                    ttl,
+                });
+            cache.import_ordered(entries).map_err(de::Error::custom)?;
+            Ok(cache)
+        }
+    }
+}
+
+// 暂停淘汰期间对缓存的独占访问，drop 时恢复淘汰
+pub struct EvictionGuard<'a, K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    cache: &'a mut Cache<K, V>,
+}
+
+impl<K, V> Deref for EvictionGuard<'_, K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    type Target = Cache<K, V>;
+
+    fn deref(&self) -> &Self::Target {
+        self.cache
+    }
+}
+
+impl<K, V> DerefMut for EvictionGuard<'_, K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.cache
+    }
+}
+
+impl<K, V> Drop for EvictionGuard<'_, K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    fn drop(&mut self) {
+        // drop 中无法返回错误，淘汰失败时缓存会在下一次 insert 时再次尝试
+        let _ = self.cache.unfreeze_eviction();
+    }
+}
+
+// 按照从尾部到头部的顺序取出缓存中的所有数据
+pub struct Drain<'a, K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    cache: &'a mut Cache<K, V>,
+}
+
+impl<K, V> Iterator for Drain<'_, K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cache.pop_lru()
+    }
+}
+
+impl<K, V> Drop for Drain<'_, K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    fn drop(&mut self) {
+        self.cache.clear();
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::thread;
+
+    use crate::lru::clock::MockClock;
+    use crate::lru::err::ListError;
+
+    use super::*;
+
+    #[test]
+    fn lru_cache_consistency() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(0);
+        assert_eq!(lru_cache.insert(0, 0), Err(CacheError::ZeroCapacity));
+
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(2);
+        // [1-1]
+        assert_eq!(lru_cache.insert(1, 1), Ok(None));
+        // [2-2 1-1]
+        assert_eq!(lru_cache.insert(2, 2), Ok(None));
+        // [1-1 2-2]
+        assert_eq!(lru_cache.query(&1), Ok(&1));
+        // [3-3 1-1]
+        assert!(lru_cache.insert(3, 3).is_ok());
+        assert_eq!(lru_cache.query(&2), Err(CacheError::CacheMiss));
+        // [1,-1 3-3]
+        assert_eq!(lru_cache.insert(1, -1), Ok(Some(1)));
+        assert_eq!(lru_cache.query(&1), Ok(&-1));
+        // [4-4 1,-1]
+        assert!(lru_cache.insert(4, 4).is_ok());
+        assert_eq!(lru_cache.query(&3), Err(CacheError::CacheMiss));
+
+        let capacity = 5;
+
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(capacity);
+        assert_eq!(lru_cache.query(&0), Err(CacheError::CacheMiss));
+
+        for ele in 0..capacity {
+            let x = ele as i32;
+            assert!(lru_cache.insert(x, x).is_ok());
+        }
+
+        for ele in 0..capacity {
+            let x = ele as i32;
+            assert_eq!(lru_cache.query(&x), Ok(&x));
+        }
+
+        let x = capacity as i32;
+        assert!(lru_cache.insert(x, x).is_ok());
+
+        assert_eq!(lru_cache.query(&x), Ok(&x));
+
+        assert_eq!(lru_cache.query(&0), Err(CacheError::CacheMiss));
+
+        let x = capacity as i32 / 2;
+        assert_eq!(lru_cache.remove(&x), Ok(x));
+
+        assert_eq!(lru_cache.query(&x), Err(CacheError::CacheMiss));
+        assert_eq!(lru_cache.remove(&x), Err(CacheError::CacheMiss));
+    }
+
+    #[test]
+    fn lru_cache_timeout() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap_timeout(5, time::Duration::from_millis(1000));
+
+        // [1-1]
+        lru_cache.insert(1, 1).unwrap();
+        assert_eq!(lru_cache.query(&1), Ok(&1));
+
+        // [2-2 1-1]
+        lru_cache.insert(2, 2).unwrap();
+        // [3-3 2-2 1-1]
+        lru_cache.insert(3, 3).unwrap();
+
+        thread::sleep(time::Duration::from_millis(500));
+        assert_eq!(lru_cache.list.len(), 3);
+
+        // [4-4 3-3 2-2 1-1]
+        lru_cache.insert(4, 4).unwrap();
+        // [5-5 4-4 3-3 2-2 1-1]
+        lru_cache.insert(5, 5).unwrap();
+        assert_eq!(lru_cache.list.len(), 5);
+
+        thread::sleep(time::Duration::from_millis(500));
+        assert_eq!(lru_cache.list.len(), 5);
+
+        // [1-1 5-5 4-4 3-3 2-2]
+        assert_eq!(lru_cache.query(&1), Ok(&1));
+        // [6-6 1-1 5-5 4-4]
+        lru_cache.insert(6, 6).unwrap();
+        assert_eq!(lru_cache.list.len(), 4);
+        assert_eq!(lru_cache.query(&2), Err(CacheError::CacheMiss));
+        assert_eq!(lru_cache.query(&3), Err(CacheError::CacheMiss));
+    }
+
+    #[test]
+    fn lru_cache_set_expiry_many() {
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(5)
+            .clock(Arc::new(clock.clone()))
+            .build();
+        for ele in 0..5 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+
+        let deadline = clock.now() + time::Duration::from_millis(300);
+        // 6 不在缓存中，不会被计数
+        assert_eq!(lru_cache.set_expiry_many(&[0, 1, 2, 3, 4, 6], deadline), 5);
+
+        clock.advance(time::Duration::from_millis(200));
+        lru_cache.retire().unwrap();
+        assert_eq!(lru_cache.len(), 5);
+        // 没有全局 timeout 时，query 和 touch 不会清除或延长单独设置的淘汰时间
+        assert_eq!(lru_cache.query(&0), Ok(&0));
+        lru_cache.touch(&1).unwrap();
+        assert_eq!(lru_cache.ttl(&0), Ok(Some(time::Duration::from_millis(100))));
+        assert_eq!(lru_cache.ttl(&1), Ok(Some(time::Duration::from_millis(100))));
+
+        // 所有 key 在同一时刻一起过期
+        clock.advance(time::Duration::from_millis(100));
+        lru_cache.retire().unwrap();
+        assert_eq!(lru_cache.len(), 0);
+        for ele in 0..5 {
+            assert_eq!(lru_cache.query(&ele), Err(CacheError::CacheMiss));
+        }
+
+        // deadline 不同时，位于链表中间甚至头部的 key 同样会按时淘汰
+        for ele in 0..5 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        let now = clock.now();
+        assert_eq!(
+            lru_cache.set_expiry_many(&[0], now + time::Duration::from_secs(3)),
+            1
+        );
+        assert_eq!(
+            lru_cache.set_expiry_many(&[2], now + time::Duration::from_secs(2)),
+            1
+        );
+        assert_eq!(
+            lru_cache.set_expiry_many(&[4], now + time::Duration::from_secs(1)),
+            1
+        );
+
+        clock.advance(time::Duration::from_secs(1));
+        assert_eq!(lru_cache.sweep(), Ok(1));
+        assert!(lru_cache.keys().eq(&[3, 2, 1, 0]));
+
+        clock.advance(time::Duration::from_secs(1));
+        lru_cache.insert(5, 5).unwrap();
+        assert!(lru_cache.keys().eq(&[5, 3, 1, 0]));
+
+        // 剩余的淘汰时间重新有序后仍然会按时淘汰尾部的 key
+        clock.advance(time::Duration::from_secs(1));
+        assert_eq!(lru_cache.sweep(), Ok(1));
+        assert!(lru_cache.keys().eq(&[5, 3, 1]));
+        assert_eq!(lru_cache.total_expired(), 8);
+    }
+
+    #[test]
+    fn lru_cache_max_value_size() {
+        let mut lru_cache = Cache::<i32, Vec<u8>>::new_with_cap(2);
+        lru_cache.set_max_value_size(4, |v| v.len());
+
+        lru_cache.insert(1, vec![1]).unwrap();
+        lru_cache.insert(2, vec![2, 2]).unwrap();
+
+        assert_eq!(
+            lru_cache.insert(3, vec![0; 5]),
+            Err(CacheError::ValueTooLarge { size: 5, max: 4 })
+        );
+        // 被拒绝的插入不会淘汰或覆盖任何数据
+        assert_eq!(
+            lru_cache.insert(1, vec![0; 5]),
+            Err(CacheError::ValueTooLarge { size: 5, max: 4 })
+        );
+        assert_eq!(lru_cache.len(), 2);
+        assert_eq!(lru_cache.query(&3), Err(CacheError::CacheMiss));
+        assert_eq!(lru_cache.query(&1), Ok(&vec![1]));
+        assert_eq!(lru_cache.query(&2), Ok(&vec![2, 2]));
+
+        assert!(lru_cache.insert(3, vec![3; 4]).is_ok());
+        assert_eq!(lru_cache.query(&3), Ok(&vec![3; 4]));
+    }
+
+    #[test]
+    fn lru_cache_insert_if_absent() {
+        let mut lru_cache = Cache::<i32, &str>::new_with_cap(3);
+        assert_eq!(lru_cache.insert_if_absent(1, "a"), Ok(true));
+        assert_eq!(lru_cache.insert_if_absent(2, "b"), Ok(true));
+        assert_eq!(lru_cache.insert_if_absent(3, "c"), Ok(true));
+
+        // key 已存在时 value 和访问顺序都保持不变
+        assert_eq!(lru_cache.insert_if_absent(1, "x"), Ok(false));
+        assert_eq!(lru_cache.peek(&1), Ok(&"a"));
+        assert_eq!(lru_cache.keys().copied().collect::<Vec<_>>(), vec![3, 2, 1]);
+
+        // 插入新数据时仍然按容量淘汰，1 位于尾部
+        // [4-d 3-c 2-b]
+        assert_eq!(lru_cache.insert_if_absent(4, "d"), Ok(true));
+        assert!(!lru_cache.contains_key(&1));
+        assert_eq!(lru_cache.len(), 3);
+    }
+
+    #[test]
+    fn lru_cache_insert_if_absent_expired() {
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, &str>::builder()
+            .capacity(3)
+            .timeout(time::Duration::from_millis(100))
+            .clock(Arc::new(clock.clone()))
+            .build();
+        assert_eq!(lru_cache.insert_if_absent(1, "a"), Ok(true));
+        assert_eq!(lru_cache.insert_if_absent(1, "b"), Ok(false));
+
+        // 已过期的 key 在插入之前被淘汰，视为不存在
+        clock.advance(time::Duration::from_millis(100));
+        assert_eq!(lru_cache.insert_if_absent(1, "b"), Ok(true));
+        assert_eq!(lru_cache.query(&1), Ok(&"b"));
+    }
+
+    #[test]
+    fn lru_cache_max_weight() {
+        let mut lru_cache = Cache::<i32, Vec<u8>>::new_with_cap(10);
+        lru_cache.set_max_weight(10, |v| v.len()).unwrap();
+
+        // [4-4 3-3 2-2 1-1]
+        for i in 1..=4 {
+            lru_cache.insert(i, vec![0; i as usize]).unwrap();
+        }
+        assert_eq!(lru_cache.current_weight(), 10);
+
+        // 需要淘汰 1、2、3 三个数据才能容纳权重为 6 的数据
+        // [5-6 4-4]
+        lru_cache.insert(5, vec![0; 6]).unwrap();
+        assert_eq!(lru_cache.len(), 2);
+        assert_eq!(lru_cache.current_weight(), 10);
+        assert_eq!(lru_cache.total_evicted(), 3);
+        assert!(lru_cache.contains_key(&4));
+        assert!(lru_cache.contains_key(&5));
+
+        // 权重超过上限的数据被拒绝，且不会淘汰任何数据
+        assert_eq!(
+            lru_cache.insert(6, vec![0; 11]),
+            Err(CacheError::ValueTooLarge { size: 11, max: 10 })
+        );
+        assert_eq!(lru_cache.len(), 2);
+
+        // 覆盖写入只计算新旧权重的差值，且不会淘汰自身
+        // [4-9]
+        lru_cache.insert(4, vec![0; 9]).unwrap();
+        assert_eq!(lru_cache.len(), 1);
+        assert_eq!(lru_cache.current_weight(), 9);
+        lru_cache.insert(4, vec![0; 1]).unwrap();
+        assert_eq!(lru_cache.current_weight(), 1);
+
+        // 删除数据时同步减去权重
+        lru_cache.insert(7, vec![0; 3]).unwrap();
+        assert_eq!(lru_cache.current_weight(), 4);
+        lru_cache.remove(&4).unwrap();
+        assert_eq!(lru_cache.current_weight(), 3);
+        lru_cache.pop_lru().unwrap();
+        assert_eq!(lru_cache.current_weight(), 0);
+    }
+
+    #[test]
+    fn lru_cache_max_weight_existing() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(4);
+        for i in 1..=4 {
+            lru_cache.insert(i, i).unwrap();
+        }
+        assert_eq!(lru_cache.current_weight(), 0);
+
+        // 已有的数据重新计算权重，超出的部分从尾部淘汰
+        lru_cache.set_max_weight(7, |v| *v as usize).unwrap();
+        assert_eq!(lru_cache.current_weight(), 7);
+        assert_eq!(lru_cache.keys().copied().collect::<Vec<_>>(), vec![4, 3]);
+
+        // 数据个数仍然受 cap 的限制
+        lru_cache.clear();
+        for i in 1..=5 {
+            lru_cache.insert(i, 1).unwrap();
+        }
+        assert_eq!(lru_cache.len(), 4);
+        assert_eq!(lru_cache.current_weight(), 4);
+
+        // upsert 原地修改后重新计算权重
+        lru_cache.upsert(5, || 0, |v| *v = 5).unwrap();
+        assert_eq!(lru_cache.current_weight(), 7);
+        assert_eq!(lru_cache.keys().copied().collect::<Vec<_>>(), vec![5, 4, 3]);
+    }
+
+    #[test]
+    fn lru_cache_advance_to() {
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(4)
+            .clock(Arc::new(clock.clone()))
+            .build();
+        let base = clock.now();
+        // [3-3 2-2 1-1 0-0]
+        for ele in 0..4 {
+            let ttl = time::Duration::from_secs(ele as u64 + 1);
+            lru_cache.insert_with_ttl(ele, ele, ttl).unwrap();
+        }
+
+        // 0、1 到期，时钟同时被推进到 midpoint
+        let midpoint = base + time::Duration::from_millis(2500);
+        assert_eq!(lru_cache.advance_to(midpoint), Ok(2));
+        assert_eq!(clock.now(), midpoint);
+        assert_eq!(lru_cache.len(), 2);
+        assert_eq!(lru_cache.query(&0), Err(CacheError::CacheMiss));
+        assert_eq!(lru_cache.query(&1), Err(CacheError::CacheMiss));
+        assert_eq!(lru_cache.advance_to(midpoint), Ok(0));
+
+        // ttl 与 sweep 都以新的时间为准
+        assert_eq!(
+            lru_cache.ttl(&2),
+            Ok(Some(time::Duration::from_millis(500)))
+        );
+        assert_eq!(
+            lru_cache.ttl(&3),
+            Ok(Some(time::Duration::from_millis(1500)))
+        );
+        clock.advance(time::Duration::from_secs(1));
+        assert_eq!(lru_cache.ttl(&2), Ok(Some(time::Duration::ZERO)));
+        assert_eq!(lru_cache.sweep(), Ok(1));
+        assert_eq!(lru_cache.query(&2), Err(CacheError::CacheMiss));
+        assert_eq!(lru_cache.query(&3), Ok(&3));
+
+        // 时钟不支持设置时只提前淘汰到期的数据
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(2);
+        lru_cache
+            .insert_with_ttl(0, 0, time::Duration::from_secs(60))
+            .unwrap();
+        let later = time::Instant::now() + time::Duration::from_secs(120);
+        assert_eq!(lru_cache.advance_to(later), Ok(1));
+        assert!(lru_cache.is_empty());
+    }
+
+    #[test]
+    fn lru_cache_query_opt() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(2);
+        lru_cache.insert(1, 1).unwrap();
+        lru_cache.insert(2, 2).unwrap();
+        lru_cache.start_recording(4);
+
+        assert_eq!(lru_cache.query_opt(&1), Ok(Some(&1)));
+        assert_eq!(lru_cache.query_opt(&3), Ok(None));
+        // 与 query 相同计入统计和访问记录
+        assert_eq!(
+            lru_cache.stats(),
+            CacheStats {
+                hits: 1,
+                misses: 1,
+                hit_rate: 0.5
+            }
+        );
+        assert_eq!(lru_cache.access_pattern(), vec![1, 3]);
+
+        // 绕过 map 直接删除链表节点，模拟缓存损坏
+        let index = *lru_cache.map.get(&2).unwrap();
+        lru_cache.list.remove(&index).unwrap();
+        assert_eq!(
+            lru_cache.query_opt(&2),
+            Err(CacheError::CacheBroken(ListError::link_broken(&index)))
+        );
+    }
+
+    #[test]
+    fn lru_cache_iter_lru_first() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(3);
+        assert_eq!(lru_cache.iter_lru_first().next(), None);
+        // [0-0 2-2 1-1]
+        for ele in 0..3 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        lru_cache.query(&0).unwrap();
+        assert!(lru_cache.iter_lru_first().eq([(&1, &1), (&2, &2), (&0, &0)]));
+
+        // 与淘汰顺序一致
+        lru_cache.insert(3, 3).unwrap();
+        assert!(lru_cache.iter_lru_first().map(|(k, _)| *k).eq([2, 0, 3]));
+    }
+
+    #[test]
+    fn lru_cache_iter_by_insertion() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(4);
+        for ele in 0..4 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+
+        // [1-1 3-3 2-2 0-0]
+        lru_cache.query(&2).unwrap();
+        lru_cache.query(&3).unwrap();
+        // 覆盖写入不改变插入顺序
+        lru_cache.insert(1, 10).unwrap();
+
+        assert!(lru_cache
+            .iter_by_insertion()
+            .eq([(&0, &0), (&1, &10), (&2, &2), (&3, &3)]));
+
+        // [4-4 1-1 3-3 2-2]
+        lru_cache.insert(4, 4).unwrap();
+        assert!(lru_cache.iter_by_insertion().map(|(k, _)| *k).eq([1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn lru_cache_extend_with() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(3);
+        lru_cache.insert(1, 100).unwrap();
+
+        let pairs = vec![(1, 1), (2, 2), (1, 10), (3, 3), (2, 20)];
+        assert!(lru_cache.extend_with(pairs, |old, new| *old += new).is_ok());
+
+        assert_eq!(lru_cache.len(), 3);
+        assert_eq!(lru_cache.query(&3), Ok(&3));
+        assert_eq!(lru_cache.query(&2), Ok(&22));
+        assert_eq!(lru_cache.query(&1), Ok(&111));
+
+        // 保留先写入的值
+        let pairs = vec![(4, 4), (4, 40)];
+        assert!(lru_cache.extend_with(pairs, |_, _| {}).is_ok());
+        assert_eq!(lru_cache.query(&4), Ok(&4));
+        assert_eq!(lru_cache.query(&3), Err(CacheError::CacheMiss));
+    }
+
+    #[test]
+    fn lru_cache_utilization_samples() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(10);
+        lru_cache.set_sample_ring_size(3);
+
+        for ele in 0..5 {
+            lru_cache.insert(ele, ele).unwrap();
+            lru_cache.sample();
+        }
+
+        let samples: Vec<_> = lru_cache.utilization_samples().collect();
+        assert_eq!(samples.len(), 3);
+        assert!(samples.iter().map(|(_, len)| *len).eq([3, 4, 5]));
+        assert!(samples.windows(2).all(|w| w[0].0 <= w[1].0));
+
+        lru_cache.set_sample_ring_size(1);
+        assert!(lru_cache.utilization_samples().map(|(_, len)| len).eq([5]));
+
+        // 自动采样
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(10)
+            .clock(Arc::new(clock.clone()))
+            .build();
+        lru_cache.set_sample_interval(Some(time::Duration::from_millis(100)));
+        lru_cache.insert(0, 0).unwrap();
+        lru_cache.insert(1, 1).unwrap();
+        clock.advance(time::Duration::from_millis(100));
+        lru_cache.insert(2, 2).unwrap();
+        assert!(lru_cache.utilization_samples().map(|(_, len)| len).eq([1, 3]));
+
+        // 覆盖写入、查询和删除同样会自动采样
+        clock.advance(time::Duration::from_millis(100));
+        lru_cache.insert(2, 20).unwrap();
+        clock.advance(time::Duration::from_millis(100));
+        lru_cache.remove(&0).unwrap();
+        clock.advance(time::Duration::from_millis(100));
+        assert_eq!(lru_cache.query(&3), Err(CacheError::CacheMiss));
+        assert!(lru_cache
+            .utilization_samples()
+            .map(|(_, len)| len)
+            .eq([1, 3, 3, 2, 2]));
+    }
+
+    #[test]
+    fn lru_cache_to_hashmap() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(3);
+        for ele in 0..5 {
+            lru_cache.insert(ele, ele * 10).unwrap();
+        }
+
+        let map = lru_cache.to_hashmap();
+        assert_eq!(map, HashMap::from([(2, 20), (3, 30), (4, 40)]));
+
+        let mut restored = Cache::from_hashmap(map.clone());
+        assert_eq!(restored.len(), 3);
+        assert_eq!(restored.to_hashmap(), map);
+        assert_eq!(restored.query(&3), Ok(&30));
+    }
+
+    #[test]
+    fn lru_cache_warm() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(5);
+        for ele in 0..5 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+
+        lru_cache.start_recording(4);
+        for key in [0, 4, 1, 3, 1, 6] {
+            let _ = lru_cache.query(&key);
+        }
+        // 只保留最近的 4 个
+        assert_eq!(lru_cache.access_pattern(), vec![1, 3, 1, 6]);
+        let pattern = lru_cache.stop_recording();
+        lru_cache.query(&2).unwrap();
+        assert!(lru_cache.access_pattern().is_empty());
+
+        let mut warmed = Cache::<i32, i32>::new_with_cap(5);
+        let mut loads = vec![];
+        let loaded = warmed.warm(&pattern, |key| {
+            loads.push(*key);
+            if *key == 6 {
+                None
+            } else {
+                Some(*key * 10)
+            }
+        });
+        assert_eq!(loaded, Ok(2));
+        assert_eq!(loads, vec![1, 3, 6]);
+        // [1-10 3-30]
+        assert!(warmed.list.iter().map(|item| item.key).eq([1, 3]));
+        assert_eq!(warmed.query(&3), Ok(&30));
+    }
+
+    #[test]
+    fn lru_cache_generation_pressure() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(2);
+        assert_eq!(lru_cache.generation_pressure(), 0.0);
+        lru_cache.insert(1, 1).unwrap();
+        assert!(lru_cache.generation_pressure() < 0.01);
+
+        lru_cache.list.arena_mut().set_generation(u64::MAX / 2);
+        assert!((lru_cache.generation_pressure() - 0.5).abs() < 0.01);
+
+        lru_cache.list.arena_mut().set_generation(u64::MAX - 1);
+        lru_cache.insert(2, 2).unwrap();
+        assert_eq!(lru_cache.list.max_generation(), u64::MAX);
+        assert!(lru_cache.generation_pressure() > 0.99);
+    }
+
+    #[test]
+    fn lru_cache_upsert() {
+        let mut lru_cache = Cache::<&str, Vec<i32>>::new_with_cap(2);
+        let mut defaults = 0;
+
+        for ele in 0..3 {
+            let value = lru_cache.upsert(
+                "a",
+                || {
+                    defaults += 1;
+                    vec![]
+                },
+                |v| v.push(ele),
+            );
+            assert_eq!(value, Ok(&(0..=ele).collect::<Vec<_>>()));
+        }
+        assert_eq!(defaults, 1);
+
+        // [b a]
+        lru_cache.upsert("b", Vec::new, |v| v.push(-1)).unwrap();
+        // [a b]
+        lru_cache.upsert("a", Vec::new, |v| v.push(3)).unwrap();
+        // [c a]
+        lru_cache.insert("c", vec![]).unwrap();
+        assert_eq!(lru_cache.query(&"b"), Err(CacheError::CacheMiss));
+        assert_eq!(lru_cache.query(&"a"), Ok(&vec![0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn lru_cache_remove_entry() {
+        let stored = String::from("key");
+        let lookup = String::from("key");
+        let mut lru_cache = Cache::<&str, i32>::new_with_cap(2);
+        lru_cache.insert(stored.as_str(), 1).unwrap();
+
+        let (key, value) = lru_cache.remove_entry(&lookup.as_str()).unwrap();
+        assert_eq!(value, 1);
+        assert_eq!(key, "key");
+        // 返回的是存储的 key，而不是用于查询的 key
+        assert_eq!(key.as_ptr(), stored.as_ptr());
+        assert_ne!(key.as_ptr(), lookup.as_ptr());
+
+        assert_eq!(lru_cache.len(), 0);
+        assert_eq!(lru_cache.remove_entry(&"key"), Err(CacheError::CacheMiss));
+    }
+
+    #[test]
+    fn lru_cache_freeze_eviction() {
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(3)
+            .timeout(time::Duration::from_millis(200))
+            .clock(Arc::new(clock.clone()))
+            .build();
+        lru_cache.insert(0, 0).unwrap();
+
+        {
+            let mut guard = lru_cache.freeze_eviction();
+            clock.advance(time::Duration::from_millis(200));
+            for ele in 1..10 {
+                guard.insert(ele, ele).unwrap();
+            }
+            // 暂停期间既没有超时淘汰也没有容量淘汰
+            assert_eq!(guard.len(), 10);
+            assert_eq!(guard.query(&1), Ok(&1));
+        }
+
+        // [1-1 9-9 8-8]，0 已超时
+        assert_eq!(lru_cache.len(), 3);
+        assert!(lru_cache.list.iter().map(|item| item.key).eq([1, 9, 8]));
+        assert_eq!(lru_cache.query(&0), Err(CacheError::CacheMiss));
+
+        // 恢复后按照原有容量淘汰
+        lru_cache.insert(10, 10).unwrap();
+        assert_eq!(lru_cache.len(), 3);
+        assert_eq!(lru_cache.query(&8), Err(CacheError::CacheMiss));
+    }
+
+    #[test]
+    fn lru_cache_eviction_events() {
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(2)
+            .timeout(time::Duration::from_millis(200))
+            .clock(Arc::new(clock.clone()))
+            .build();
+        let events = lru_cache.eviction_events();
+
+        for ele in 0..4 {
+            lru_cache.insert(ele, ele * 10).unwrap();
+        }
+        clock.advance(time::Duration::from_millis(200));
+        lru_cache.insert(4, 40).unwrap();
+
+        let received: Vec<_> = events.try_iter().collect();
+        assert_eq!(
+            received,
+            vec![
+                (0, 0, EvictionReason::Capacity),
+                (1, 10, EvictionReason::Capacity),
+                (2, 20, EvictionReason::Expired),
+                (3, 30, EvictionReason::Expired),
+            ]
+        );
+
+        // 接收端被 drop 后不影响缓存
+        drop(events);
+        lru_cache.insert(5, 50).unwrap();
+        lru_cache.insert(6, 60).unwrap();
+        assert!(lru_cache.eviction_tx.is_none());
+    }
+
+    #[test]
+    fn lru_cache_on_evict() {
+        let clock = MockClock::new();
+        let evicted = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(2)
+            .timeout(time::Duration::from_secs(10))
+            .clock(Arc::new(clock.clone()))
+            .build();
+        let sink = Arc::clone(&evicted);
+        lru_cache.on_evict(move |key, value| sink.lock().unwrap().push((key, value)));
+
+        for ele in 0..4 {
+            lru_cache.insert(ele, ele * 10).unwrap();
+        }
+        assert_eq!(*evicted.lock().unwrap(), vec![(0, 0), (1, 10)]);
+
+        clock.advance(time::Duration::from_secs(10));
+        lru_cache.insert(4, 40).unwrap();
+        assert_eq!(*evicted.lock().unwrap(), vec![(0, 0), (1, 10), (2, 20), (3, 30)]);
+        assert_eq!(lru_cache.map.len(), lru_cache.list.len());
+
+        // 同时订阅淘汰事件时，回调和通道都会收到被淘汰的数据
+        let events = lru_cache.eviction_events();
+        lru_cache.insert(5, 50).unwrap();
+        lru_cache.insert(6, 60).unwrap();
+        assert_eq!(evicted.lock().unwrap()[4..], [(4, 40)]);
+        assert!(events.try_iter().eq([(4, 40, EvictionReason::Capacity)]));
+
+        // 重新注册回调不影响订阅
+        lru_cache.on_evict(|_, _| {});
+        lru_cache.insert(7, 70).unwrap();
+        assert_eq!(evicted.lock().unwrap().len(), 5);
+        assert!(events.try_iter().eq([(5, 50, EvictionReason::Capacity)]));
+    }
+
+    #[test]
+    fn lru_cache_get_or_insert_with_no_reposition() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(3);
+        // [2-2 1-1 0-0]
+        for ele in 0..3 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+
+        let mut calls = 0;
+        let v = lru_cache.get_or_insert_with_no_reposition(0, || {
+            calls += 1;
+            -1
+        });
+        assert_eq!(v, Ok(&0));
+        assert_eq!(calls, 0);
+        assert!(lru_cache.list.iter().map(|item| item.key).eq([2, 1, 0]));
+
+        // [3-3 2-2 1-1]
+        let v = lru_cache.get_or_insert_with_no_reposition(3, || 3);
+        assert_eq!(v, Ok(&3));
+        assert!(lru_cache.list.iter().map(|item| item.key).eq([3, 2, 1]));
+    }
+
+    #[test]
+    fn lru_cache_get_or_try_insert_with() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(2);
+        // [1-1 0-0]
+        for ele in 0..2 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+
+        // 命中时不会调用 f
+        let v = lru_cache.get_or_try_insert_with(0, || Err::<i32, &str>("unreachable"));
+        assert_eq!(v, Ok(&0));
+        assert!(lru_cache.keys().eq(&[0, 1]));
+
+        // f 失败时缓存保持不变，容量已满也不会淘汰数据
+        let v = lru_cache.get_or_try_insert_with(2, || Err("fetch failed"));
+        assert_eq!(v, Err(CacheOrUserError::User("fetch failed")));
+        assert!(lru_cache.keys().eq(&[0, 1]));
+        assert!(!lru_cache.contains_key(&2));
+        assert_eq!(lru_cache.total_evicted(), 0);
+
+        // f 成功时插入到头部 [2-2 0-0]
+        let v = lru_cache.get_or_try_insert_with(2, || Ok::<i32, &str>(2));
+        assert_eq!(v, Ok(&2));
+        assert!(lru_cache.keys().eq(&[2, 0]));
+
+        // 缓存本身的错误
+        let mut lru_cache = Cache::<i32, i32>::new();
+        let v = lru_cache.get_or_try_insert_with(0, || Ok::<i32, &str>(0));
+        assert_eq!(v, Err(CacheOrUserError::Cache(CacheError::ZeroCapacity)));
+        assert_eq!(
+            CacheOrUserError::<&str>::User("fetch failed").to_string(),
+            "Failed to compute value: fetch failed"
+        );
+
+        // source 指向调用方的错误，或者 CacheError 自身的 source
+        let user_error = CacheOrUserError::User(fmt::Error);
+        assert_eq!(
+            std::error::Error::source(&user_error).map(|source| source.to_string()),
+            Some(fmt::Error.to_string())
+        );
+        let cache_error =
+            CacheOrUserError::<fmt::Error>::Cache(CacheError::CacheBroken(ListError::ListEmpty));
+        assert_eq!(
+            std::error::Error::source(&cache_error).map(|source| source.to_string()),
+            Some(ListError::ListEmpty.to_string())
+        );
+        let cache_miss = CacheOrUserError::<fmt::Error>::Cache(CacheError::CacheMiss);
+        assert!(std::error::Error::source(&cache_miss).is_none());
+    }
+
+    #[test]
+    fn lru_cache_get_or_insert_with() {
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(3)
+            .timeout(time::Duration::from_millis(200))
+            .clock(Arc::new(clock.clone()))
+            .build();
+        // [2-2 1-1 0-0]
+        for ele in 0..3 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+
+        let mut calls = 0;
+        let v = lru_cache.get_or_insert_with(0, || {
+            calls += 1;
+            -1
+        });
+        assert_eq!(v, Ok(&0));
+        assert_eq!(calls, 0);
+        assert!(lru_cache.list.iter().map(|item| item.key).eq([0, 2, 1]));
+
+        // 容量已满，淘汰 1 [3-3 0-0 2-2]
+        let v = lru_cache.get_or_insert_with(3, || 3);
+        assert_eq!(v, Ok(&3));
+        assert!(lru_cache.list.iter().map(|item| item.key).eq([3, 0, 2]));
+        assert_eq!(lru_cache.map.len(), 3);
+
+        // 超时的数据会被淘汰后重新计算
+        clock.advance(time::Duration::from_millis(200));
+        let v = lru_cache.get_or_insert_with(0, || {
+            calls += 1;
+            -1
+        });
+        assert_eq!(v, Ok(&-1));
+        assert_eq!(calls, 1);
+        assert_eq!(lru_cache.len(), 1);
+    }
+
+    #[test]
+    fn lru_cache_iter() {
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(3)
+            .timeout(time::Duration::from_millis(100))
+            .clock(Arc::new(clock.clone()))
+            .build();
+        assert_eq!(lru_cache.iter().next(), None);
+        // [2-2 1-1 0-0]
+        for ele in 0..3 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        // [0-0 2-2 1-1]
+        lru_cache.query(&0).unwrap();
+        assert!(lru_cache.iter().eq([(&0, &0), (&2, &2), (&1, &1)]));
+        // 遍历不会改变访问顺序
+        assert!(lru_cache.iter().eq([(&0, &0), (&2, &2), (&1, &1)]));
+
+        // 已超时但还没有被淘汰的数据仍会被遍历到
+        clock.advance(time::Duration::from_millis(100));
+        assert_eq!(lru_cache.len(), 0);
+        assert_eq!(lru_cache.iter().count(), 3);
+    }
+
+    #[test]
+    fn lru_cache_keys_values() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(3);
+        // [2-2 1-1 0-0]
+        for ele in 0..3 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        // [1-1 2-2 0-0]
+        lru_cache.query(&1).unwrap();
+        assert!(lru_cache.keys().eq(&[1, 2, 0]));
+        assert!(lru_cache.values().eq(&[1, 2, 0]));
+
+        for value in lru_cache.values_mut() {
+            *value *= 10;
+        }
+        assert!(lru_cache.keys().eq(&[1, 2, 0]));
+        assert!(lru_cache.values().eq(&[10, 20, 0]));
+        assert_eq!(lru_cache.query(&2), Ok(&20));
+        assert_eq!(lru_cache.values_mut().count(), 3);
+    }
+
+    #[test]
+    fn lru_cache_entries_accessed_within() {
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(5)
+            .clock(Arc::new(clock.clone()))
+            .build();
+        for ele in 0..5 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        let window = time::Duration::from_millis(100);
+        assert_eq!(lru_cache.entries_accessed_within(window), 5);
+
+        clock.advance(time::Duration::from_millis(150));
+        assert_eq!(lru_cache.entries_accessed_within(window), 0);
+
+        lru_cache.query(&1).unwrap();
+        lru_cache.query(&3).unwrap();
+        assert_eq!(lru_cache.entries_accessed_within(window), 2);
+        assert_eq!(lru_cache.entries_accessed_within(time::Duration::from_secs(10)), 5);
+    }
+
+    #[test]
+    fn lru_cache_query_with_grace() {
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(2)
+            .timeout(time::Duration::from_millis(400))
+            .grace(time::Duration::from_millis(200))
+            .clock(Arc::new(clock.clone()))
+            .build();
+        lru_cache.insert(1, 1).unwrap();
+        lru_cache.insert(2, 2).unwrap();
+
+        assert_eq!(lru_cache.query_with_grace(&1), Ok(Freshness::Fresh(&1)));
+        assert_eq!(lru_cache.query_with_grace(&3), Ok(Freshness::Miss));
+
+        // 过期但仍在宽限期内
+        clock.advance(time::Duration::from_millis(450));
+        assert_eq!(lru_cache.query_with_grace(&1), Ok(Freshness::Stale(&1)));
+        assert_eq!(lru_cache.query_with_grace(&2), Ok(Freshness::Stale(&2)));
+        // 宽限期内不会被淘汰
+        lru_cache.retire().unwrap();
+        assert_eq!(lru_cache.len(), 2);
+        // 刷新数据
+        lru_cache.insert(2, 20).unwrap();
+
+        // 宽限期结束
+        clock.advance(time::Duration::from_millis(200));
+        assert_eq!(lru_cache.query_with_grace(&1), Ok(Freshness::Miss));
+        assert_eq!(lru_cache.query_with_grace(&2), Ok(Freshness::Fresh(&20)));
+        assert_eq!(lru_cache.len(), 1);
+        // Fresh 和 Stale 计为命中，Miss 计为未命中
+        let stats = lru_cache.stats();
+        assert_eq!((stats.hits, stats.misses), (4, 2));
+
+        // 空闲超过 max_idle 的数据不享有宽限期
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(2)
+            .timeout(time::Duration::from_millis(400))
+            .max_idle(time::Duration::from_millis(100))
+            .grace(time::Duration::from_millis(200))
+            .clock(Arc::new(clock.clone()))
+            .build();
+        lru_cache.insert(1, 1).unwrap();
+        clock.advance(time::Duration::from_millis(150));
+        assert_eq!(lru_cache.query_with_grace(&1), Ok(Freshness::Miss));
+        assert_eq!(lru_cache.len(), 0);
+        assert_eq!(lru_cache.total_expired(), 1);
+        assert_eq!(lru_cache.stats().misses, 1);
+    }
+
+    #[test]
+    fn lru_cache_query_opts() {
+        let timeout = time::Duration::from_millis(1000);
+        let clock = MockClock::new();
+        let expire_time = |cache: &Cache<i32, i32>, key: i32| {
+            let index = cache.map.get(&key).unwrap();
+            cache.list.get(index).unwrap().expire_time().unwrap()
+        };
+        let keys = |cache: &Cache<i32, i32>| {
+            cache.list.iter().map(|item| item.key).collect::<Vec<_>>()
+        };
+
+        for reposition in [false, true] {
+            for refresh_ttl in [false, true] {
+                for count_stats in [false, true] {
+                    let mut lru_cache = Cache::<i32, i32>::builder()
+                        .capacity(3)
+                        .timeout(timeout)
+                        .clock(Arc::new(clock.clone()))
+                        .build();
+                    // [2-2 1-1 0-0]
+                    for ele in 0..3 {
+                        lru_cache.insert(ele, ele).unwrap();
+                    }
+                    let old_expire_time = expire_time(&lru_cache, 0);
+                    clock.advance(time::Duration::from_millis(10));
+
+                    let opts = QueryOpts {
+                        reposition,
+                        refresh_ttl,
+                        count_stats,
+                    };
+                    assert_eq!(lru_cache.query_opts(&0, opts), Ok(&0));
+                    assert_eq!(lru_cache.query_opts(&3, opts), Err(CacheError::CacheMiss));
+
+                    let expected = if reposition { [0, 2, 1] } else { [2, 1, 0] };
+                    assert_eq!(keys(&lru_cache), expected);
+                    assert_eq!(expire_time(&lru_cache, 0) > old_expire_time, refresh_ttl);
+                    let stats = if count_stats { (1, 1) } else { (0, 0) };
+                    assert_eq!((lru_cache.hits, lru_cache.misses), stats);
+                }
+            }
+        }
+
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(1);
+        lru_cache.insert(0, 0).unwrap();
+        assert_eq!(lru_cache.query(&0), Ok(&0));
+        assert_eq!((lru_cache.hits, lru_cache.misses), (1, 0));
+    }
+
+    #[test]
+    fn lru_cache_get_many_or_load() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(10);
+        lru_cache.insert(1, 1).unwrap();
+        lru_cache.insert(3, 3).unwrap();
+
+        let mut calls = vec![];
+        let values = lru_cache.get_many_or_load(&[1, 2, 3, 4, 2, 5], |keys| {
+            calls.push(keys.to_vec());
+            // 5 不存在于数据源中
+            keys.iter()
+                .filter(|key| **key != 5)
+                .map(|key| (*key, *key * 10))
+                .collect()
+        });
+        assert_eq!(
+            values,
+            Ok(vec![Some(1), Some(20), Some(3), Some(40), Some(20), None])
+        );
+        assert_eq!(calls, vec![vec![2, 4, 5]]);
+        assert_eq!(lru_cache.len(), 4);
+
+        // 全部命中时不调用 batch_loader
+        let values = lru_cache.get_many_or_load(&[2, 4], |_| panic!("should not load"));
+        assert_eq!(values, Ok(vec![Some(20), Some(40)]));
+    }
+
+    #[test]
+    fn lru_cache_get_many_or_load_over_capacity() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(2);
+        lru_cache.insert(0, 0).unwrap();
+
+        // 未命中的 key 多于容量，先加载的 value 被淘汰后仍然返回
+        let values = lru_cache.get_many_or_load(&[0, 1, 2, 3], |keys| {
+            keys.iter().map(|key| (*key, *key * 10)).collect()
+        });
+        assert_eq!(values, Ok(vec![Some(0), Some(10), Some(20), Some(30)]));
+        assert!(lru_cache.keys().eq(&[3, 2]));
+
+        // 无法缓存的 value 不会中断整个批量加载
+        let mut lru_cache = Cache::<i32, Vec<u8>>::new_with_cap(4);
+        lru_cache.set_max_value_size(2, |v| v.len());
+        let values = lru_cache.get_many_or_load(&[1, 2], |keys| {
+            keys.iter()
+                .map(|key| (*key, vec![0; *key as usize * 2]))
+                .collect()
+        });
+        assert_eq!(values, Ok(vec![Some(vec![0; 2]), Some(vec![0; 4])]));
+        assert!(lru_cache.contains_key(&1));
+        assert!(!lru_cache.contains_key(&2));
+    }
+
+    #[test]
+    fn lru_cache_compact() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(10);
+        for ele in 0..10 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert_eq!(lru_cache.fragmentation(), 0.0);
+
+        // 空闲链表：8 -> 6 -> 4 -> 2 -> 0
+        for ele in (0..10).step_by(2) {
+            lru_cache.remove(&ele).unwrap();
+        }
+        assert_eq!(lru_cache.fragmentation(), 1.0);
+
+        lru_cache.compact().unwrap();
+        assert_eq!(lru_cache.fragmentation(), 0.0);
+        assert!(lru_cache.list.iter().map(|item| item.key).eq([9, 7, 5, 3, 1]));
+        for ele in (1..10).step_by(2) {
+            assert_eq!(lru_cache.query(&ele), Ok(&ele));
+        }
+        for ele in 10..15 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert_eq!(lru_cache.len(), 10);
+
+        // 自动整理：不相邻地删除会使碎片程度升高
+        lru_cache.set_auto_compact_threshold(Some(0.5));
+        for ele in [10, 12, 14, 11, 13] {
+            lru_cache.remove(&ele).unwrap();
+            assert!(lru_cache.fragmentation() <= 0.5);
+        }
+        for ele in (1..10).step_by(2) {
+            assert_eq!(lru_cache.query(&ele), Ok(&ele));
+        }
+    }
+
+    #[test]
+    fn lru_cache_export_ordered() {
+        let timeout = time::Duration::from_millis(1000);
+        let clock = MockClock::new();
+        let build = || {
+            Cache::<i32, i32>::builder()
+                .capacity(4)
+                .timeout(timeout)
+                .clock(Arc::new(clock.clone()))
+                .build()
+        };
+        let mut lru_cache = build();
+        for ele in 0..4 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        clock.advance(time::Duration::from_millis(200));
+        // [1-1 3-3 2-2 0-0]
+        lru_cache.query(&3).unwrap();
+        lru_cache.query(&1).unwrap();
+
+        let exported = lru_cache.export_ordered();
+        assert!(exported.iter().map(|entry| entry.key).eq([0, 2, 3, 1]));
+
+        let mut follower = build();
+        follower.import_ordered(exported.clone()).unwrap();
+        let imported = follower.export_ordered();
+        assert_eq!(imported.len(), exported.len());
+        for (a, b) in exported.iter().zip(&imported) {
+            assert_eq!((a.key, a.value), (b.key, b.value));
+            assert_eq!(a.created_at, b.created_at);
+            assert_eq!(a.last_accessed, b.last_accessed);
+            assert_eq!(a.ttl, b.ttl);
+        }
+        // 先插入的数据剩余时间更短
+        assert!(imported[0].ttl < imported[3].ttl);
+
+        // 导入后的淘汰顺序与原缓存一致
+        follower.insert(4, 4).unwrap();
+        assert_eq!(follower.query(&0), Err(CacheError::CacheMiss));
+    }
+
+    #[test]
+    fn lru_cache_total_evicted_expired() {
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(3)
+            .timeout(time::Duration::from_millis(200))
+            .clock(Arc::new(clock.clone()))
+            .build();
+        for ele in 0..5 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert_eq!(lru_cache.total_evicted(), 2);
+        assert_eq!(lru_cache.total_expired(), 0);
+
+        // 手动删除不计入
+        lru_cache.remove(&4).unwrap();
+        assert_eq!(lru_cache.total_evicted(), 2);
+
+        clock.advance(time::Duration::from_millis(200));
+        lru_cache.insert(5, 5).unwrap();
+        assert_eq!(lru_cache.total_evicted(), 2);
+        assert_eq!(lru_cache.total_expired(), 2);
+
+        for ele in 6..9 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert_eq!(lru_cache.total_evicted(), 3);
+        assert_eq!(lru_cache.total_expired(), 2);
+    }
+
+    #[test]
+    fn lru_cache_restore_ordered() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(4);
+        let spread = time::Duration::from_secs(3);
+        lru_cache
+            .restore_ordered(vec![(3, 3), (1, 1), (0, 0), (2, 2)], spread)
+            .unwrap();
+
+        // 访问时间按照恢复顺序递增且互不相同
+        let last_access: Vec<_> = [3, 1, 0, 2]
+            .iter()
+            .map(|key| {
+                let index = lru_cache.map.get(key).unwrap();
+                lru_cache.list.get(index).unwrap().last_access()
+            })
+            .collect();
+        assert!(last_access.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(last_access[3] - last_access[0], spread);
+        assert_eq!(lru_cache.entries_accessed_within(time::Duration::from_millis(1500)), 2);
+
+        // 最先恢复的数据最先被淘汰
+        lru_cache.insert(4, 4).unwrap();
+        assert_eq!(lru_cache.query(&3), Err(CacheError::CacheMiss));
+        lru_cache.insert(5, 5).unwrap();
+        assert_eq!(lru_cache.query(&1), Err(CacheError::CacheMiss));
+
+        // spread 过大时不会溢出，淘汰顺序仍然与恢复顺序一致
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(3);
+        lru_cache
+            .restore_ordered(vec![(0, 0), (1, 1), (2, 2)], time::Duration::MAX)
+            .unwrap();
+        assert!(lru_cache.keys().eq(&[2, 1, 0]));
+        lru_cache.insert(3, 3).unwrap();
+        assert_eq!(lru_cache.query(&0), Err(CacheError::CacheMiss));
+    }
+
+    #[test]
+    fn lru_cache_reconfigure() {
+        let clock = MockClock::new();
+        let timeout = time::Duration::from_secs(10);
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(5)
+            .timeout(timeout)
+            .clock(Arc::new(clock.clone()))
+            .build();
+        let events = lru_cache.eviction_events();
+        // [3-3 4-4 2-2 1-1 0-0]，访问次数 0:3 1:1 2:1 3:2 4:1
+        for ele in 0..4 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        lru_cache
+            .insert_with_ttl(4, 4, time::Duration::from_secs(60))
+            .unwrap();
+        clock.advance(time::Duration::from_secs(5));
+        lru_cache.query(&0).unwrap();
+        lru_cache.query(&0).unwrap();
+        lru_cache.query(&3).unwrap();
+
+        // 切换为 LFU 并缩容，保留访问次数最多的 0、3，以及访问次数相同时最近访问的 4
+        lru_cache.reconfigure(3, EvictionPolicy::Lfu).unwrap();
+        assert_eq!(lru_cache.eviction_policy(), EvictionPolicy::Lfu);
+        assert_eq!(lru_cache.len(), 3);
+        assert_eq!(lru_cache.capacity(), 3);
+        assert_eq!(lru_cache.list.capacity(), 3);
+        assert!(lru_cache.keys().eq(&[3, 0, 4]));
+        assert!(events.try_iter().map(|(key, _, _)| key).eq([1, 2]));
+        assert_eq!(lru_cache.total_evicted(), 2);
+
+        // 访问时间和淘汰时间保持不变，包括单独设置的存活时间
+        assert_eq!(lru_cache.ttl(&0), Ok(Some(timeout)));
+        assert_eq!(lru_cache.ttl(&4), Ok(Some(time::Duration::from_secs(55))));
+        assert_eq!(lru_cache.idle_time(&4), Ok(time::Duration::from_secs(5)));
+
+        // 按照访问次数淘汰：4 最近访问过，但访问次数最少
+        lru_cache.insert(5, 5).unwrap();
+        assert!(lru_cache.keys().eq(&[5, 3, 0]));
+        // 5 的访问次数增加后，淘汰访问次数相同中最久未访问的 3
+        lru_cache.query(&5).unwrap();
+        lru_cache.insert(6, 6).unwrap();
+        assert!(lru_cache.keys().eq(&[6, 5, 0]));
+
+        // 切换回 LRU 并扩容，之后淘汰最久未访问的 0，即使它的访问次数最多
+        lru_cache.reconfigure(10, EvictionPolicy::Lru).unwrap();
+        for ele in 7..15 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert_eq!(lru_cache.len(), 10);
+        assert_eq!(lru_cache.peek(&0), Err(CacheError::CacheMiss));
+        assert_eq!(lru_cache.peek_lru(), Some((&5, &5)));
+    }
+
+    #[test]
+    fn lru_cache_into_lfu() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(5);
+        // [3-3 4-4 2-2 1-1 0-0]，访问次数 0:2 3:2，其余为 1
+        for ele in 0..5 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        lru_cache.query(&0).unwrap();
+        lru_cache.query(&3).unwrap();
+
+        // 保留访问次数最多的 0、3，以及访问次数相同时最近访问的 4
+        let mut lfu_cache = lru_cache.into_lfu(3).unwrap();
+        assert_eq!(lfu_cache.len(), 3);
+        assert_eq!(lfu_cache.capacity(), 3);
+        for key in [1, 2] {
+            assert_eq!(lfu_cache.peek(&key), Err(CacheError::CacheMiss));
+        }
+        // 沿用原有的访问次数
+        assert_eq!(lfu_cache.frequency(&0), Ok(2));
+        assert_eq!(lfu_cache.frequency(&3), Ok(2));
+        assert_eq!(lfu_cache.frequency(&4), Ok(1));
+
+        // 之后按照访问次数淘汰：4 访问次数最少
+        lfu_cache.insert(5, 5).unwrap();
+        assert_eq!(lfu_cache.peek(&4), Err(CacheError::CacheMiss));
+        // 访问次数相同时淘汰最久未访问的 5
+        lfu_cache.insert(6, 6).unwrap();
+        assert_eq!(lfu_cache.peek(&5), Err(CacheError::CacheMiss));
+        // 0 比 3 更久未访问
+        lfu_cache.query(&6).unwrap();
+        lfu_cache.insert(7, 7).unwrap();
+        assert_eq!(lfu_cache.peek(&0), Err(CacheError::CacheMiss));
+        assert_eq!(lfu_cache.peek(&3), Ok(&3));
+    }
+
+    #[test]
+    fn lru_cache_peek() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(2);
+        // [2-2 1-1]
+        lru_cache.insert(1, 1).unwrap();
+        lru_cache.insert(2, 2).unwrap();
+
+        assert_eq!(lru_cache.peek(&1), Ok(&1));
+        assert_eq!(lru_cache.peek(&3), Err(CacheError::CacheMiss));
+        assert_eq!((lru_cache.hits, lru_cache.misses), (0, 0));
+
+        // peek 不改变访问顺序，1 仍然最先被淘汰
+        lru_cache.insert(3, 3).unwrap();
+        assert_eq!(lru_cache.peek(&1), Err(CacheError::CacheMiss));
+        assert_eq!(lru_cache.peek(&2), Ok(&2));
+    }
+
+    #[test]
+    fn lru_cache_contains_key() {
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(2)
+            .timeout(time::Duration::from_millis(200))
+            .clock(Arc::new(clock.clone()))
+            .build();
+        // [2-2 1-1]
+        lru_cache.insert(1, 1).unwrap();
+        lru_cache.insert(2, 2).unwrap();
+
+        assert!(lru_cache.contains_key(&1));
+        assert!(!lru_cache.contains_key(&3));
+
+        // contains_key 不改变访问顺序
+        lru_cache.insert(3, 3).unwrap();
+        assert!(!lru_cache.contains_key(&1));
+        assert!(lru_cache.contains_key(&2));
+
+        // 已过期但还没有被淘汰
+        clock.advance(time::Duration::from_millis(200));
+        assert_eq!(lru_cache.list.len(), 2);
+        assert!(!lru_cache.contains_key(&2));
+        assert!(!lru_cache.contains_key(&3));
+
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(1);
+        lru_cache.insert(1, 1).unwrap();
+        assert!(lru_cache.contains_key(&1));
+    }
+
+    #[test]
+    fn lru_cache_len() {
+        let clock = MockClock::new();
+        let build = |policy| {
+            Cache::<i32, i32>::builder()
+                .capacity(3)
+                .timeout(time::Duration::from_millis(200))
+                .expiration(policy)
+                .clock(Arc::new(clock.clone()))
+                .build()
+        };
+        let mut lru_cache = build(ExpirationPolicy::Sliding);
+        assert!(lru_cache.is_empty());
+        assert_eq!(lru_cache.len(), 0);
+
+        lru_cache.insert(1, 1).unwrap();
+        lru_cache.insert(2, 2).unwrap();
+        assert!(!lru_cache.is_empty());
+        assert_eq!(lru_cache.len(), 2);
+
+        clock.advance(time::Duration::from_millis(100));
+        lru_cache.insert(3, 3).unwrap();
+        assert_eq!(lru_cache.len(), 3);
+
+        // 1、2 已过期但还没有被淘汰，不计入
+        clock.advance(time::Duration::from_millis(100));
+        assert_eq!(lru_cache.list.len(), 3);
+        assert_eq!(lru_cache.len(), 1);
+        assert!(!lru_cache.is_empty());
+
+        clock.advance(time::Duration::from_millis(100));
+        assert_eq!(lru_cache.len(), 0);
+        assert!(lru_cache.is_empty());
+
+        // Fixed 策略下被访问的数据移动到头部但保留原有的淘汰时间，
+        // 过期数据位于未过期数据之前时同样不计入
+        let mut lru_cache = build(ExpirationPolicy::Fixed);
+        lru_cache.insert(1, 1).unwrap();
+        clock.advance(time::Duration::from_millis(100));
+        lru_cache.insert(2, 2).unwrap();
+        lru_cache.query(&1).unwrap();
+        assert!(lru_cache.keys().eq(&[1, 2]));
+        clock.advance(time::Duration::from_millis(100));
+        assert_eq!(lru_cache.list.len(), 2);
+        assert_eq!(lru_cache.len(), 1);
+        assert_eq!(lru_cache.sweep(), Ok(1));
+        assert_eq!(lru_cache.len(), 1);
+        assert!(lru_cache.keys().eq(&[2]));
+    }
+
+    #[test]
+    fn lru_cache_capacity() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(4);
+        assert_eq!(lru_cache.capacity(), 4);
+        for ele in 0..6 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert_eq!(lru_cache.capacity(), 4);
+        assert_eq!(lru_cache.len() as f64 / lru_cache.capacity() as f64, 1.0);
+
+        {
+            let mut guard = lru_cache.freeze_eviction();
+            for ele in 6..10 {
+                guard.insert(ele, ele).unwrap();
+            }
+            assert_eq!(guard.capacity(), 4);
+        }
+        assert_eq!(lru_cache.capacity(), 4);
+
+        lru_cache.reconfigure(8, EvictionPolicy::Lru).unwrap();
+        assert_eq!(lru_cache.capacity(), 8);
+        assert_eq!(Cache::<i32, i32>::new_with_cap(0).capacity(), 0);
+    }
+
+    #[test]
+    fn lru_cache_string_keys() {
+        let mut lru_cache = Cache::<String, i32>::new_with_cap(2);
+        lru_cache.insert("a".to_string(), 1).unwrap();
+        lru_cache.insert("b".to_string(), 2).unwrap();
+        assert_eq!(lru_cache.query(&"a".to_string()), Ok(&1));
+
+        // "b" 是最久未访问的键，会被淘汰
+        lru_cache.insert("c".to_string(), 3).unwrap();
+        assert_eq!(lru_cache.query(&"b".to_string()), Err(CacheError::CacheMiss));
+
+        lru_cache.insert("a".to_string(), 10).unwrap();
+        assert_eq!(lru_cache.query(&"a".to_string()), Ok(&10));
+        assert_eq!(lru_cache.remove(&"c".to_string()), Ok(3));
+        assert_eq!(lru_cache.len(), 1);
+
+        let map = lru_cache.to_hashmap();
+        assert_eq!(map.get("a"), Some(&10));
+    }
+
+    #[test]
+    fn lru_cache_stats() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(2);
+        assert_eq!(lru_cache.stats(), CacheStats { hits: 0, misses: 0, hit_rate: 0.0 });
+
+        lru_cache.insert(1, 1).unwrap();
+        lru_cache.insert(2, 2).unwrap();
+        lru_cache.query(&1).unwrap();
+        lru_cache.query(&2).unwrap();
+        lru_cache.query(&1).unwrap();
+        lru_cache.query(&3).unwrap_err();
+        // peek 不计入统计
+        lru_cache.peek(&2).unwrap();
+        lru_cache.peek(&3).unwrap_err();
+        assert_eq!(lru_cache.stats(), CacheStats { hits: 3, misses: 1, hit_rate: 0.75 });
+
+        lru_cache.reset_stats();
+        assert_eq!(lru_cache.stats(), CacheStats { hits: 0, misses: 0, hit_rate: 0.0 });
+        lru_cache.query(&4).unwrap_err();
+        assert_eq!(lru_cache.stats(), CacheStats { hits: 0, misses: 1, hit_rate: 0.0 });
+    }
+
+    #[test]
+    fn lru_cache_clear() {
+        let mut lru_cache =
+            Cache::<i32, i32>::new_with_cap_timeout(3, time::Duration::from_secs(10));
+        for ele in 0..3 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        lru_cache.remove(&1).unwrap();
+        lru_cache.insert(3, 3).unwrap();
+
+        lru_cache.clear();
+        assert!(lru_cache.is_empty());
+        assert!(lru_cache.map.is_empty());
+        assert_eq!(lru_cache.query(&0), Err(CacheError::CacheMiss));
+        assert_eq!(lru_cache.capacity(), 3);
+        assert_eq!(lru_cache.list.fragmentation(), 0.0);
+
+        for ele in 10..13 {
+            assert_eq!(lru_cache.insert(ele, ele), Ok(None));
+        }
+        assert_eq!(lru_cache.len(), 3);
+        assert_eq!(lru_cache.total_evicted(), 0);
+        assert!(lru_cache.list.iter().map(|item| item.key).eq([12, 11, 10]));
+        assert_eq!(lru_cache.list.timeout(), Some(time::Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn lru_cache_drain() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(5);
+        // [2-2 1-1 0-0]
+        for ele in 0..3 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        // [0-0 2-2 1-1]
+        lru_cache.query(&0).unwrap();
+        let drained: Vec<_> = lru_cache.drain().collect();
+        assert_eq!(drained, vec![(1, 1), (2, 2), (0, 0)]);
+        assert_eq!(lru_cache.len(), 0);
+        assert!(lru_cache.map.is_empty());
+
+        // 提前 drop 迭代器，剩余的数据也会被删除
+        for ele in 0..5 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert_eq!(lru_cache.drain().next(), Some((0, 0)));
+        assert_eq!(lru_cache.len(), 0);
+        assert!(lru_cache.map.is_empty());
+        assert_eq!(lru_cache.query(&4), Err(CacheError::CacheMiss));
+
+        // 缓存可以继续使用
+        for ele in 0..5 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert_eq!(lru_cache.len(), 5);
+        assert_eq!(lru_cache.total_evicted(), 0);
+    }
+
+    #[test]
+    fn lru_cache_retain() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(8);
+        // [7-7 6-6 5-5 4-4 3-3 2-2 1-1 0-0]
+        for ele in 0..8 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        // [1-1 7-7 6-6 5-5 4-4 3-3 2-2 0-0]
+        lru_cache.query(&1).unwrap();
+        // 同时删除头部、中间和尾部的数据
+        lru_cache.retain(|_, value| value % 2 == 0 && *value != 0).unwrap();
+        assert!(lru_cache.keys().eq(&[6, 4, 2]));
+        assert_eq!(lru_cache.map.len(), 3);
+        assert_eq!(lru_cache.query(&1), Err(CacheError::CacheMiss));
+        assert_eq!(lru_cache.query(&0), Err(CacheError::CacheMiss));
+        assert_eq!(lru_cache.query(&4), Ok(&4));
+        assert!(lru_cache.keys().eq(&[4, 6, 2]));
+
+        lru_cache.retain(|_, _| false).unwrap();
+        assert!(lru_cache.is_empty());
+        assert!(lru_cache.map.is_empty());
+    }
+
+    #[test]
+    fn lru_cache_remove_many() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(8);
+        // [7-7 6-6 5-5 4-4 3-3 2-2 1-1 0-0]
+        for ele in 0..8 {
+            lru_cache.insert(ele, ele * 10).unwrap();
+        }
+        // 同时删除头部、中间和尾部的数据，不存在和重复的 key 返回 None
+        assert_eq!(
+            lru_cache.remove_many(&[7, 8, 3, 0, 3]),
+            vec![Some(70), None, Some(30), Some(0), None]
+        );
+        assert!(lru_cache.keys().eq(&[6, 5, 4, 2, 1]));
+        assert_eq!(lru_cache.map.len(), 5);
+
+        // 删除后头尾指针仍然正确
+        lru_cache.insert(8, 80).unwrap();
+        assert_eq!(lru_cache.peek_mru(), Some((&8, &80)));
+        assert_eq!(lru_cache.peek_lru(), Some((&1, &10)));
+    }
+
+    #[test]
+    fn lru_cache_remove_if() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(8);
+        // [7-7 6-6 5-5 4-4 3-3 2-2 1-1 0-0]
+        for ele in 0..8 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert_eq!(lru_cache.remove_if(|key| key % 3 == 1 || *key == 0), 4);
+        assert!(lru_cache.keys().eq(&[6, 5, 3, 2]));
+        assert_eq!(lru_cache.map.len(), 4);
+        assert_eq!(lru_cache.peek_lru(), Some((&2, &2)));
+
+        assert_eq!(lru_cache.remove_if(|_| false), 0);
+        assert_eq!(lru_cache.remove_if(|_| true), 4);
+        assert!(lru_cache.is_empty());
+        assert!(lru_cache.map.is_empty());
+    }
+
+    #[test]
+    fn lru_cache_insert_with_ttl() {
+        // 没有全局 timeout 的缓存
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(3)
+            .clock(Arc::new(clock.clone()))
+            .build();
+        lru_cache.insert(0, 0).unwrap();
+        lru_cache.insert_with_ttl(1, 1, time::Duration::from_millis(100)).unwrap();
+        assert!(lru_cache.contains_key(&1));
+
+        clock.advance(time::Duration::from_millis(100));
+        assert!(!lru_cache.contains_key(&1));
+        lru_cache.insert(2, 2).unwrap();
+        // 1 位于链表中间，淘汰时间不一致时同样会被淘汰
+        assert!(lru_cache.keys().eq(&[2, 0]));
+        lru_cache.remove(&0).unwrap();
+        lru_cache.insert(3, 3).unwrap();
+        assert!(lru_cache.keys().eq(&[3, 2]));
+        assert_eq!(lru_cache.total_expired(), 1);
+
+        // retire_all 可以淘汰位于链表中间的过期数据
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(3)
+            .clock(Arc::new(clock.clone()))
+            .build();
+        lru_cache.insert(3, 3).unwrap();
+        lru_cache.insert_with_ttl(4, 4, time::Duration::from_millis(100)).unwrap();
+        lru_cache.insert(5, 5).unwrap();
+        clock.advance(time::Duration::from_millis(100));
+        assert_eq!(lru_cache.retire_all(), Ok(1));
+        assert!(lru_cache.keys().eq(&[5, 3]));
+        assert_eq!(lru_cache.map.len(), 2);
+        assert_eq!(lru_cache.total_expired(), 1);
+
+        // ttl 与全局 timeout 无关，覆盖已存在的 key 时同样生效
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(3)
+            .timeout(time::Duration::from_secs(10))
+            .clock(Arc::new(clock.clone()))
+            .build();
+        lru_cache.insert(0, 0).unwrap();
+        assert_eq!(
+            lru_cache.insert_with_ttl(0, 10, time::Duration::from_millis(100)),
+            Ok(Some(0))
+        );
+        clock.advance(time::Duration::from_millis(100));
+        lru_cache.insert(1, 1).unwrap();
+        assert_eq!(lru_cache.query(&0), Err(CacheError::CacheMiss));
+        assert_eq!(lru_cache.query(&1), Ok(&1));
+        // 再次 insert 时按照全局 timeout 重新计算
+        lru_cache
+            .insert_with_ttl(1, 1, time::Duration::from_millis(100))
+            .unwrap();
+        lru_cache.insert(1, 1).unwrap();
+        assert_eq!(lru_cache.ttl(&1), Ok(Some(time::Duration::from_secs(10))));
+    }
+
+    #[test]
+    fn lru_cache_insert_with_ttl_query() {
+        // 没有全局 timeout 时，query 按照数据自身的 ttl 延长淘汰时间，而不是清除淘汰时间
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(3)
+            .clock(Arc::new(clock.clone()))
+            .build();
+        lru_cache
+            .insert_with_ttl(0, 0, time::Duration::from_millis(100))
+            .unwrap();
+        lru_cache.insert(1, 1).unwrap();
+        clock.advance(time::Duration::from_millis(50));
+        assert_eq!(lru_cache.query(&0), Ok(&0));
+        assert_eq!(
+            lru_cache.ttl(&0),
+            Ok(Some(time::Duration::from_millis(100)))
+        );
+        assert_eq!(lru_cache.ttl(&1), Ok(None));
+
+        clock.advance(time::Duration::from_millis(100));
+        assert!(!lru_cache.contains_key(&0));
+        assert_eq!(lru_cache.sweep(), Ok(1));
+        assert!(lru_cache.keys().eq(&[1]));
+
+        // 不移动到头部时同样按照自身的 ttl 刷新
+        lru_cache
+            .insert_with_ttl(2, 2, time::Duration::from_millis(100))
+            .unwrap();
+        clock.advance(time::Duration::from_millis(50));
+        let opts = QueryOpts {
+            reposition: false,
+            ..QueryOpts::default()
+        };
+        assert_eq!(lru_cache.query_opts(&2, opts), Ok(&2));
+        assert_eq!(
+            lru_cache.ttl(&2),
+            Ok(Some(time::Duration::from_millis(100)))
+        );
+        clock.advance(time::Duration::from_millis(100));
+        assert_eq!(lru_cache.sweep(), Ok(1));
+
+        // ttl 过大导致溢出时视为永不过期，访问时同样不会溢出
+        lru_cache
+            .insert_with_ttl(3, 3, time::Duration::MAX)
+            .unwrap();
+        assert_eq!(lru_cache.ttl(&3), Ok(None));
+        assert_eq!(lru_cache.query(&3), Ok(&3));
+        assert_eq!(lru_cache.ttl(&3), Ok(None));
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap_timeout(1, time::Duration::MAX);
+        lru_cache.insert(0, 0).unwrap();
+        assert_eq!(lru_cache.query(&0), Ok(&0));
+        assert_eq!(lru_cache.ttl(&0), Ok(None));
+    }
+
+    #[test]
+    fn lru_cache_ttl() {
+        let timeout = time::Duration::from_millis(200);
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(3)
+            .timeout(timeout)
+            .clock(Arc::new(clock.clone()))
+            .build();
+        lru_cache.insert(0, 0).unwrap();
+        assert_eq!(lru_cache.ttl(&0), Ok(Some(timeout)));
+        clock.advance(time::Duration::from_millis(50));
+        assert_eq!(lru_cache.ttl(&0), Ok(Some(time::Duration::from_millis(150))));
+        assert_eq!(lru_cache.ttl(&1), Err(CacheError::CacheMiss));
+
+        clock.advance(time::Duration::from_millis(150));
+        assert_eq!(lru_cache.ttl(&0), Ok(Some(time::Duration::ZERO)));
+
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(3)
+            .clock(Arc::new(clock.clone()))
+            .build();
+        lru_cache.insert(0, 0).unwrap();
+        assert_eq!(lru_cache.ttl(&0), Ok(None));
+        lru_cache.insert_with_ttl(1, 1, timeout).unwrap();
+        assert_eq!(lru_cache.ttl(&1), Ok(Some(timeout)));
+    }
+
+    #[test]
+    fn lru_cache_touch() {
+        let timeout = time::Duration::from_millis(200);
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(3)
+            .timeout(timeout)
+            .clock(Arc::new(clock.clone()))
+            .build();
+        // [2-2 1-1 0-0]
+        for ele in 0..3 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        clock.advance(time::Duration::from_millis(100));
+
+        // [0-0 2-2 1-1]
+        assert_eq!(lru_cache.touch(&0), Ok(()));
+        assert!(lru_cache.keys().eq(&[0, 2, 1]));
+        assert_eq!(lru_cache.ttl(&0), Ok(Some(timeout)));
+        assert_eq!(lru_cache.touch(&3), Err(CacheError::CacheMiss));
+        assert_eq!(lru_cache.stats().hits + lru_cache.stats().misses, 0);
+
+        // 被 touch 的数据没有过期，其它数据被淘汰
+        clock.advance(time::Duration::from_millis(100));
+        lru_cache.insert(3, 3).unwrap();
+        assert!(lru_cache.keys().eq(&[3, 0]));
+        assert_eq!(lru_cache.query(&0), Ok(&0));
+    }
+
+    #[test]
+    fn lru_cache_reorder_keeps_index() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(5);
+        for ele in 0..5 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        let indices: Vec<Index> = (0..5).map(|key| lru_cache.map[&key]).collect();
+
+        // 移动到头部的操作都是原地移动，map 中的 index 保持不变
+        lru_cache.touch(&0).unwrap();
+        lru_cache.upsert(1, || 0, |value| *value += 10).unwrap();
+        lru_cache
+            .extend_with([(2, 2)], |old, new| *old += new)
+            .unwrap();
+        *lru_cache.entry(3).unwrap().or_insert(0).unwrap() += 30;
+        lru_cache.insert(4, 40).unwrap();
+        assert!(lru_cache.keys().eq(&[4, 3, 2, 1, 0]));
+        assert!(lru_cache.values().eq(&[40, 33, 4, 11, 0]));
+        for (key, index) in indices.iter().enumerate() {
+            assert_eq!(lru_cache.map[&(key as i32)], *index);
+        }
+    }
+
+    #[test]
+    fn lru_cache_peek_lru_mru() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(3);
+        assert_eq!(lru_cache.peek_lru(), None);
+        assert_eq!(lru_cache.peek_mru(), None);
+
+        // [2-2 1-1 0-0]
+        for ele in 0..3 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert_eq!(lru_cache.peek_lru(), Some((&0, &0)));
+        assert_eq!(lru_cache.peek_mru(), Some((&2, &2)));
+
+        // [0-0 2-2 1-1]
+        lru_cache.query(&0).unwrap();
+        assert_eq!(lru_cache.peek_lru(), Some((&1, &1)));
+        assert_eq!(lru_cache.peek_mru(), Some((&0, &0)));
+        assert!(lru_cache.keys().eq(&[0, 2, 1]));
+
+        // 下一次插入会淘汰 peek_lru 返回的数据
+        lru_cache.insert(3, 3).unwrap();
+        assert_eq!(lru_cache.query(&1), Err(CacheError::CacheMiss));
+        assert_eq!(lru_cache.peek_lru(), Some((&2, &2)));
+    }
+
+    #[test]
+    fn lru_cache_pop_lru() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(4);
+        assert_eq!(lru_cache.pop_lru(), None);
+        // [3-3 2-2 1-1 0-0]
+        for ele in 0..4 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        // [1-1 3-3 2-2 0-0]
+        lru_cache.query(&1).unwrap();
+
+        let mut popped = vec![];
+        while let Some(entry) = lru_cache.pop_lru() {
+            popped.push(entry);
+            assert_eq!(lru_cache.map.len(), lru_cache.list.len());
+        }
+        assert_eq!(popped, vec![(0, 0), (2, 2), (3, 3), (1, 1)]);
+        assert!(lru_cache.is_empty());
+        assert_eq!(lru_cache.total_evicted(), 0);
+
+        lru_cache.insert(5, 5).unwrap();
+        assert_eq!(lru_cache.query(&5), Ok(&5));
+    }
+
+    #[test]
+    fn lru_cache_debug() {
+        let mut lru_cache = Cache::<i32, &str>::new_with_cap(3);
+        lru_cache.insert(1, "a").unwrap();
+        lru_cache.insert(2, "b").unwrap();
+        lru_cache.query(&1).unwrap();
+        assert_eq!(
+            format!("{lru_cache:?}"),
+            r#"Cache { cap: 3, timeout: None, entries: {1: "a", 2: "b"} }"#
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn lru_cache_serde_round_trip() {
+        let timeout = time::Duration::from_secs(10);
+        let mut lru_cache = Cache::<String, i32>::new_with_cap_timeout(3, timeout);
+        // [c-3 a-1 b-2]
+        lru_cache.insert("a".to_string(), 1).unwrap();
+        lru_cache.insert("b".to_string(), 2).unwrap();
+        lru_cache.query(&"a".to_string()).unwrap();
+        lru_cache.insert("c".to_string(), 3).unwrap();
+
+        let json = serde_json::to_string(&lru_cache).unwrap();
+        let mut restored: Cache<String, i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.capacity(), 3);
+        assert_eq!(restored.list.timeout(), Some(timeout));
+        assert!(restored.keys().eq(["c", "a", "b"]));
+        let ttl = restored.ttl(&"b".to_string()).unwrap().unwrap();
+        assert!(ttl <= timeout && ttl > time::Duration::from_secs(9));
+
+        // 容量淘汰的顺序与原缓存一致
+        restored.insert("d".to_string(), 4).unwrap();
+        assert!(restored.keys().eq(["d", "c", "a"]));
+
+        // 没有 timeout 的缓存，以及已过期的数据
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(3)
+            .clock(Arc::new(clock.clone()))
+            .build();
+        lru_cache.insert(0, 0).unwrap();
+        lru_cache.insert_with_ttl(1, 1, time::Duration::from_millis(50)).unwrap();
+        clock.advance(time::Duration::from_millis(50));
+        let json = serde_json::to_string(&lru_cache).unwrap();
+        let restored: Cache<i32, i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.list.timeout(), None);
+        assert!(restored.keys().eq(&[0]));
+        assert_eq!(restored.ttl(&0), Ok(None));
+    }
+
+    #[test]
+    fn lru_cache_clone() {
+        let mut lru_cache =
+            Cache::<i32, i32>::new_with_cap_timeout(3, time::Duration::from_secs(10));
+        // [0-0 2-2 1-1]
+        for ele in 0..3 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        lru_cache.query(&0).unwrap();
+
+        let mut cloned = lru_cache.clone();
+        assert!(cloned.iter().eq(lru_cache.iter()));
+        let expire_time =
+            |cache: &Cache<i32, i32>| cache.list.get(&cache.map[&1]).unwrap().expire_time();
+        assert_eq!(expire_time(&cloned), expire_time(&lru_cache));
+        assert_eq!(cloned.stats(), lru_cache.stats());
+
+        // 修改副本不影响原缓存
+        cloned.insert(0, 10).unwrap();
+        cloned.insert(3, 3).unwrap();
+        cloned.remove(&2).unwrap();
+        assert!(cloned.keys().eq(&[3, 0]));
+        assert!(lru_cache.keys().eq(&[0, 2, 1]));
+        assert_eq!(lru_cache.query(&0), Ok(&0));
+        assert_eq!(cloned.query(&0), Ok(&10));
+        assert_eq!(lru_cache.map.len(), 3);
+    }
+
+    #[test]
+    fn lru_cache_from_iter() {
+        let pairs = vec![(0, 0), (1, 10), (2, 20)];
+        let mut lru_cache: Cache<i32, i32> = pairs.clone().into_iter().collect();
+        assert_eq!(lru_cache.capacity(), 3);
+        assert_eq!(lru_cache.list.timeout(), None);
+        assert!(lru_cache.keys().eq(&[2, 1, 0]));
+
+        let mut collected: Vec<(i32, i32)> = lru_cache.drain().collect();
+        collected.sort();
+        assert_eq!(collected, pairs);
+
+        let lru_cache: Cache<i32, i32> = std::iter::empty().collect();
+        assert_eq!(lru_cache.capacity(), 0);
+        assert!(lru_cache.is_empty());
+    }
+
+    #[test]
+    fn lru_cache_extend() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(3);
+        lru_cache.insert(0, 0).unwrap();
+        lru_cache.extend([(1, 1), (2, 2)]);
+        assert!(lru_cache.keys().eq(&[2, 1, 0]));
+
+        // 超出容量时按照正常规则淘汰
+        lru_cache.extend((3..6).map(|ele| (ele, ele)));
+        assert!(lru_cache.keys().eq(&[5, 4, 3]));
+        assert_eq!(lru_cache.total_evicted(), 3);
+        assert_eq!(lru_cache.map.len(), 3);
+
+        // 超过大小限制的数据被跳过
+        lru_cache.set_max_value_size(10, |value| *value as usize);
+        lru_cache.extend([(6, 6), (7, 100), (8, 8)]);
+        assert!(lru_cache.keys().eq(&[8, 6, 5]));
+    }
+
+    #[test]
+    fn lru_cache_try_reserve() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(2);
+        lru_cache.insert(0, 0).unwrap();
+        lru_cache.insert(1, 1).unwrap();
+
+        // 申请失败时返回错误而不是终止进程，缓存保持不变
+        let oom = Err(CacheError::CacheBroken(ListError::ListOOM(ArenaOOM {})));
+        assert_eq!(lru_cache.try_reserve(usize::MAX / 2), oom);
+        assert_eq!(lru_cache.try_reserve(usize::MAX), oom);
+        assert_eq!(lru_cache.capacity(), 2);
+        assert!(lru_cache.keys().eq(&[1, 0]));
+
+        // [3-3 2-2 1-1 0-0]
+        assert_eq!(lru_cache.try_reserve(2), Ok(()));
+        assert_eq!(lru_cache.capacity(), 4);
+        lru_cache.insert(2, 2).unwrap();
+        lru_cache.insert(3, 3).unwrap();
+        assert!(lru_cache.keys().eq(&[3, 2, 1, 0]));
+    }
+
+    #[test]
+    fn lru_cache_error_from() {
+        // 底层的错误可以直接通过 ? 传递到缓存层
+        fn arena_oom() -> Result<(), CacheError> {
+            Err(ArenaOOM {})?
+        }
+        fn list_empty() -> Result<(), CacheError> {
+            Err(ListError::ListEmpty)?
+        }
+        assert_eq!(
+            arena_oom(),
+            Err(CacheError::CacheBroken(ListError::ListOOM(ArenaOOM {})))
+        );
+        assert_eq!(
+            list_empty(),
+            Err(CacheError::CacheBroken(ListError::ListEmpty))
+        );
+        assert_eq!(
+            ListError::from(ArenaOOM {}),
+            ListError::ListOOM(ArenaOOM {})
+        );
+    }
+
+    #[test]
+    fn lru_cache_error_source() {
+        use std::error::Error;
+
+        let error: Box<dyn Error> = Box::new(CacheError::from(ArenaOOM {}));
+        let mut chain = vec![error.to_string()];
+        let mut source = error.source();
+        while let Some(inner) = source {
+            chain.push(inner.to_string());
+            source = inner.source();
+        }
+        assert_eq!(
+            chain,
+            [
+                "Cache storage is broken: List out of memory: Arena out of memory.",
+                "List out of memory: Arena out of memory.",
+                "Arena out of memory.",
+            ]
+        );
+        assert!(error.downcast_ref::<CacheError>().is_some());
+        assert!(CacheError::CacheMiss.source().is_none());
+        assert!(ListError::ListEmpty.source().is_none());
+    }
+
+    #[test]
+    fn lru_cache_growth() {
+        let mut lru_cache =
+            Cache::<i32, i32>::new_with_cap_growth(2, GrowthPolicy::Grow { max: Some(6) });
+        // 2 -> 4 -> 6，扩容期间保留所有数据
+        for ele in 0..6 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert_eq!(lru_cache.len(), 6);
+        assert_eq!(lru_cache.capacity(), 6);
+        assert_eq!(lru_cache.total_evicted(), 0);
+        assert!(lru_cache.keys().eq(&[5, 4, 3, 2, 1, 0]));
+
+        // 达到 max 后按照 LRU 淘汰
+        // [6-6 5-5 4-4 3-3 2-2 1-1]
+        lru_cache.insert(6, 6).unwrap();
+        assert_eq!(lru_cache.len(), 6);
+        assert_eq!(lru_cache.capacity(), 6);
+        assert!(!lru_cache.contains_key(&0));
+        assert_eq!(lru_cache.total_evicted(), 1);
+
+        // 不限制容量
+        let mut lru_cache =
+            Cache::<i32, i32>::new_with_cap_growth(1, GrowthPolicy::Grow { max: None });
+        for ele in 0..100 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert_eq!(lru_cache.len(), 100);
+        assert_eq!(lru_cache.capacity(), 128);
+        assert!((0..100).all(|ele| lru_cache.contains_key(&ele)));
+
+        // 默认按照 LRU 淘汰
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap_growth(2, GrowthPolicy::default());
+        for ele in 0..3 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert_eq!(lru_cache.capacity(), 2);
+        assert!(lru_cache.keys().eq(&[2, 1]));
+    }
+
+    #[test]
+    fn lru_cache_reject_on_full() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap_growth(2, GrowthPolicy::Reject);
+        lru_cache.insert(0, 0).unwrap();
+        lru_cache.insert(1, 1).unwrap();
+
+        // 已满时拒绝新数据，不淘汰任何数据
+        assert_eq!(
+            lru_cache.insert(2, 2),
+            Err(CacheError::CapacityExceeded {
+                needed: 1,
+                available: 0
+            })
+        );
+        assert!(lru_cache.keys().eq(&[1, 0]));
+        assert_eq!(lru_cache.total_evicted(), 0);
+        assert_eq!(
+            lru_cache.insert(2, 2).unwrap_err().to_string(),
+            "Cache is full: needed 1, available 0."
+        );
+
+        // 覆盖写入不受影响，删除数据后可以继续插入
+        assert_eq!(lru_cache.insert(0, 10), Ok(Some(0)));
+        lru_cache.remove(&1).unwrap();
+        assert_eq!(lru_cache.insert(2, 2), Ok(None));
+        assert!(lru_cache.keys().eq(&[2, 0]));
+    }
+
+    #[test]
+    fn lru_cache_reject_on_full_weight() {
+        let mut lru_cache = Cache::<i32, Vec<u8>>::builder()
+            .capacity(10)
+            .growth(GrowthPolicy::Reject)
+            .build();
+        lru_cache.set_max_weight(10, |v| v.len()).unwrap();
+        lru_cache.insert(1, vec![0; 4]).unwrap();
+        lru_cache.insert(2, vec![0; 4]).unwrap();
+
+        assert_eq!(
+            lru_cache.insert(3, vec![0; 3]),
+            Err(CacheError::CapacityExceeded {
+                needed: 3,
+                available: 2
+            })
+        );
+        assert_eq!(lru_cache.len(), 2);
+        assert_eq!(lru_cache.current_weight(), 8);
+        assert_eq!(lru_cache.total_evicted(), 0);
+
+        // 覆盖写入只需要容纳新旧权重的差值，被拒绝时保留旧的 value
+        assert_eq!(
+            lru_cache.insert(1, vec![0; 7]),
+            Err(CacheError::CapacityExceeded {
+                needed: 7,
+                available: 6
+            })
+        );
+        assert_eq!(lru_cache.peek(&1), Ok(&vec![0; 4]));
+        assert_eq!(lru_cache.insert(1, vec![0; 6]), Ok(Some(vec![0; 4])));
+        assert_eq!(lru_cache.current_weight(), 10);
+
+        // 暂停淘汰期间通过扩容容纳新数据
+        let mut guard = lru_cache.freeze_eviction();
+        assert_eq!(guard.insert(3, vec![0; 3]), Ok(None));
+        assert_eq!(guard.current_weight(), 13);
+    }
+
+    #[test]
+    fn lru_cache_reject_on_full_reweigh() {
+        let mut lru_cache = Cache::<i32, Vec<u8>>::builder()
+            .capacity(10)
+            .growth(GrowthPolicy::Reject)
+            .build();
+        lru_cache.set_max_weight(10, |v| v.len()).unwrap();
+        lru_cache.insert(1, vec![0; 4]).unwrap();
+        lru_cache.insert(2, vec![0; 4]).unwrap();
+
+        // 原地修改后没有超过上限
+        assert_eq!(
+            lru_cache.upsert(1, Vec::new, |v| v.push(0)),
+            Ok(&vec![0; 5])
+        );
+        assert_eq!(lru_cache.current_weight(), 9);
+
+        // 超过上限时不淘汰其它数据
+        assert_eq!(
+            lru_cache.upsert(2, Vec::new, |v| v.extend([0; 3])),
+            Err(CacheError::CapacityExceeded {
+                needed: 7,
+                available: 5
+            })
+        );
+        assert_eq!(lru_cache.len(), 2);
+        assert_eq!(lru_cache.total_evicted(), 0);
+        assert_eq!(lru_cache.current_weight(), 12);
+        assert_eq!(
+            lru_cache.extend_with([(1, vec![0])], |v, extra| v.extend(extra)),
+            Err(CacheError::CapacityExceeded {
+                needed: 6,
+                available: 3
+            })
+        );
+        assert_eq!(lru_cache.total_evicted(), 0);
+
+        // 总权重回到上限以内之前拒绝新数据
+        assert_eq!(
+            lru_cache.insert(3, vec![0]),
+            Err(CacheError::CapacityExceeded {
+                needed: 1,
+                available: 0
+            })
+        );
+        lru_cache.remove(&2).unwrap();
+        assert_eq!(lru_cache.insert(3, vec![0]), Ok(None));
+    }
+
+    #[test]
+    fn lru_cache_admission() {
+        // 偶数次访问 5 个热点 key，奇数次访问只出现一次的 key
+        // 相同热点 key 的两次访问之间有 9 个不同的 key，容量为 4 的 LRU 总是未命中
+        fn hits(lru_cache: &mut Cache<i32, i32>) -> usize {
+            let mut hits = 0;
+            for i in 0..2000 {
+                let key = if i % 2 == 0 { (i / 2) % 5 } else { 1000 + i };
+                if lru_cache.query(&key).is_ok() {
+                    hits += 1;
+                } else {
+                    match lru_cache.insert(key, key) {
+                        Ok(_) | Err(CacheError::NotAdmitted) => {}
+                        Err(e) => panic!("{e}"),
+                    }
+                }
+            }
+            hits
+        }
+
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(4);
+        let lru_hits = hits(&mut lru_cache);
+        let mut tiny_lfu = Cache::<i32, i32>::new_with_cap(4);
+        tiny_lfu.set_admission_sketch(256, 4);
+        let tiny_lfu_hits = hits(&mut tiny_lfu);
+        assert_eq!(lru_hits, 0);
+        assert!(tiny_lfu_hits > 400, "{tiny_lfu_hits}");
+
+        // 被拒绝的插入不会淘汰任何数据
+        // 频率统计足够大，避免随机的哈希冲突使几个 key 的估计值偏高
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(2);
+        lru_cache.set_admission_sketch(4096, 4);
+        lru_cache.insert(0, 0).unwrap();
+        lru_cache.insert(1, 1).unwrap();
+        lru_cache.query(&0).unwrap();
+        // [0-0 1-1]
+        assert_eq!(lru_cache.query(&2), Err(CacheError::CacheMiss));
+        assert_eq!(lru_cache.insert(2, 2), Err(CacheError::NotAdmitted));
+        assert!(lru_cache.keys().eq(&[0, 1]));
+        // 被拒绝的插入同样计入访问频率，高于尾部的数据后可以被插入
+        assert_eq!(lru_cache.insert(2, 2), Ok(None));
+        assert!(lru_cache.keys().eq(&[2, 0]));
+    }
+
+    #[test]
+    fn lru_cache_admission_computed_value() {
+        fn admission_cache() -> Cache<i32, i32> {
+            let mut lru_cache = Cache::<i32, i32>::new_with_cap(2);
+            lru_cache.set_admission_sketch(4096, 4);
+            lru_cache.insert(0, 0).unwrap();
+            lru_cache.insert(1, 1).unwrap();
+            lru_cache.query(&0).unwrap();
+            lru_cache
+        }
+
+        // 没有通过准入策略时返回计算得到的 value，但不缓存
+        let mut lru_cache = admission_cache();
+        assert_eq!(lru_cache.get_or_insert_with(2, || 20), Ok(&20));
+        assert!(lru_cache.keys().eq(&[0, 1]));
+        assert_eq!(
+            lru_cache.get_or_insert_with_no_reposition(3, || 30),
+            Ok(&30)
+        );
+        assert!(lru_cache.keys().eq(&[0, 1]));
+
+        let mut lru_cache = admission_cache();
+        let value = lru_cache.get_or_try_insert_with(2, || Ok::<_, ()>(20));
+        assert_eq!(value.ok(), Some(&20));
+        assert!(lru_cache.keys().eq(&[0, 1]));
+
+        let mut lru_cache = admission_cache();
+        let value = lru_cache.entry(2).unwrap().or_insert(20).unwrap();
+        *value += 1;
+        assert_eq!(*value, 21);
+        assert!(lru_cache.keys().eq(&[0, 1]));
+
+        // insert_many 跳过没有通过准入策略的数据，继续插入之后的数据
+        let mut lru_cache = admission_cache();
+        assert_eq!(lru_cache.insert_many(vec![(2, 2), (0, 10), (2, 2)]), Ok(()));
+        assert_eq!(lru_cache.peek(&0), Ok(&10));
+        assert!(lru_cache.keys().eq(&[2, 0]));
+    }
+
+    #[test]
+    fn lru_cache_builder() {
+        // 只设置容量时与 new_with_cap 相同
+        let mut lru_cache = Cache::<i32, i32>::builder().capacity(2).build();
+        for ele in 0..3 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert_eq!(lru_cache.capacity(), 2);
+        assert!(lru_cache.keys().eq(&[2, 1]));
+
+        // 淘汰回调
+        let evicted = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&evicted);
+        let mut lru_cache = CacheBuilder::new()
+            .capacity(1)
+            .growth(GrowthPolicy::Grow { max: Some(2) })
+            .on_evict(move |key, value| sink.lock().unwrap().push((key, value)))
+            .build();
+        for ele in 0..3 {
+            lru_cache.insert(ele, ele * 10).unwrap();
+        }
+        assert_eq!(lru_cache.capacity(), 2);
+        assert_eq!(*evicted.lock().unwrap(), vec![(0, 0)]);
+
+        // 超时淘汰，Fixed 策略下访问不会延长淘汰时间
+        let clock = MockClock::new();
+        let mut lru_cache = CacheBuilder::new()
+            .capacity(2)
+            .timeout(time::Duration::from_millis(100))
+            .expiration(ExpirationPolicy::Fixed)
+            .clock(Arc::new(clock.clone()))
+            .build();
+        lru_cache.insert(0, 0).unwrap();
+        clock.advance(time::Duration::from_millis(60));
+        assert_eq!(lru_cache.query(&0), Ok(&0));
+        clock.advance(time::Duration::from_millis(60));
+        lru_cache.insert(1, 1).unwrap();
+        assert!(lru_cache.keys().eq(&[1]));
+
+        // 没有设置容量
+        let mut lru_cache = CacheBuilder::<i32, i32>::new().build();
+        assert_eq!(lru_cache.capacity(), 0);
+        assert!(lru_cache.insert(0, 0).is_err());
+    }
+
+    #[test]
+    fn lru_cache_reserve() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(2);
+        lru_cache.insert(0, 0).unwrap();
+        lru_cache.insert(1, 1).unwrap();
+        lru_cache.reserve(3);
+        assert_eq!(lru_cache.capacity(), 5);
+        for ele in 2..5 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert_eq!(lru_cache.len(), 5);
+        assert_eq!(lru_cache.total_evicted(), 0);
+        assert!(lru_cache.keys().eq(&[4, 3, 2, 1, 0]));
+
+        // 容量已满后恢复淘汰
+        lru_cache.insert(5, 5).unwrap();
+        assert!(!lru_cache.contains_key(&0));
+        assert_eq!(lru_cache.len(), 5);
+    }
+
+    #[test]
+    fn lru_cache_mock_clock() {
+        let clock = MockClock::new();
+        let mut lru_cache = CacheBuilder::new()
+            .capacity(3)
+            .timeout(time::Duration::from_secs(60))
+            .clock(Arc::new(clock.clone()))
+            .build();
+        lru_cache.insert(0, 0).unwrap();
+        clock.advance(time::Duration::from_secs(30));
+        lru_cache.insert(1, 1).unwrap();
+        assert_eq!(lru_cache.ttl(&0), Ok(Some(time::Duration::from_secs(30))));
+
+        // 0 已过期，1 还剩 30 秒
+        clock.advance(time::Duration::from_secs(30));
+        lru_cache.insert(2, 2).unwrap();
+        assert!(lru_cache.keys().eq(&[2, 1]));
+
+        // 重建后沿用同一个时间来源
+        lru_cache.reconfigure(3, EvictionPolicy::Lru).unwrap();
+        clock.advance(time::Duration::from_secs(60));
+        assert_eq!(lru_cache.sweep(), Ok(2));
+        assert!(lru_cache.is_empty());
+    }
+
+    #[test]
+    fn lru_cache_idle_time() {
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(3);
+        lru_cache.set_clock(Arc::new(clock.clone()));
+        lru_cache.insert(0, 0).unwrap();
+        lru_cache.insert(1, 1).unwrap();
+
+        clock.advance(time::Duration::from_secs(10));
+        assert_eq!(lru_cache.idle_time(&0), Ok(time::Duration::from_secs(10)));
+
+        // query 和 touch 都会重置空闲时间，peek 不会
+        lru_cache.query(&0).unwrap();
+        assert_eq!(lru_cache.idle_time(&0), Ok(time::Duration::ZERO));
+        clock.advance(time::Duration::from_secs(3));
+        lru_cache.peek(&0).unwrap();
+        assert_eq!(lru_cache.idle_time(&0), Ok(time::Duration::from_secs(3)));
+        lru_cache.touch(&1).unwrap();
+        assert_eq!(lru_cache.idle_time(&1), Ok(time::Duration::ZERO));
+
+        assert_eq!(lru_cache.idle_time(&2), Err(CacheError::CacheMiss));
+    }
+
+    #[test]
+    fn lru_cache_max_idle() {
+        let clock = MockClock::new();
+        let mut lru_cache = CacheBuilder::new()
+            .capacity(4)
+            .timeout(time::Duration::from_secs(3600))
+            .max_idle(time::Duration::from_secs(10))
+            .clock(Arc::new(clock.clone()))
+            .build();
+        for ele in 0..3 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+
+        // 0 被访问后重新计算空闲时间，1 和 2 空闲超过 10 秒
+        clock.advance(time::Duration::from_secs(6));
+        lru_cache.query(&0).unwrap();
+        clock.advance(time::Duration::from_secs(6));
+        assert!(lru_cache.contains_key(&0));
+        assert!(!lru_cache.contains_key(&1));
+        assert_eq!(lru_cache.len(), 1);
+        lru_cache.insert(3, 3).unwrap();
+        assert!(lru_cache.keys().eq(&[3, 0]));
+        assert_eq!(lru_cache.total_expired(), 2);
+
+        // 空闲淘汰不依赖 timeout
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(2);
+        lru_cache.set_clock(Arc::new(clock.clone()));
+        lru_cache.set_max_idle(time::Duration::from_secs(10));
+        lru_cache.insert(0, 0).unwrap();
+        clock.advance(time::Duration::from_secs(11));
+        assert_eq!(lru_cache.sweep(), Ok(1));
+        assert!(lru_cache.is_empty());
+    }
+
+    #[test]
+    fn lru_cache_warm_up() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(3);
+        lru_cache.set_admission_sketch(4096, 4);
+        lru_cache.warm_up((0..5).map(|ele| (ele, ele * 10))).unwrap();
+        // 只保留最后 3 个，第一个位于尾部
+        assert!(lru_cache.keys().eq(&[4, 3, 2]));
+        assert_eq!(lru_cache.peek_lru(), Some((&2, &20)));
+        assert_eq!(lru_cache.total_evicted(), 2);
+
+        // 插入期间不会执行超时淘汰
+        let clock = MockClock::new();
+        let mut lru_cache = CacheBuilder::new()
+            .capacity(3)
+            .timeout(time::Duration::from_secs(10))
+            .clock(Arc::new(clock.clone()))
+            .build();
+        lru_cache.insert(0, 0).unwrap();
+        clock.advance(time::Duration::from_secs(10));
+        lru_cache.warm_up([(1, 1), (2, 2)]).unwrap();
+        assert_eq!(lru_cache.list.len(), 3);
+        assert_eq!(lru_cache.total_expired(), 0);
+        // 之后的 insert 恢复自动淘汰
+        lru_cache.insert(3, 3).unwrap();
+        assert!(lru_cache.keys().eq(&[3, 2, 1]));
+        assert_eq!(lru_cache.total_expired(), 1);
+    }
+
+    #[test]
+    fn lru_cache_expired_count() {
+        let clock = MockClock::new();
+        let mut lru_cache = CacheBuilder::new()
+            .capacity(4)
+            .timeout(time::Duration::from_secs(10))
+            .clock(Arc::new(clock.clone()))
+            .build();
+        lru_cache.insert(0, 0).unwrap();
+        lru_cache.insert(1, 1).unwrap();
+        clock.advance(time::Duration::from_secs(5));
+        lru_cache.insert(2, 2).unwrap();
+        assert_eq!((lru_cache.expired_count(), lru_cache.live_count()), (0, 3));
+
+        clock.advance(time::Duration::from_secs(5));
+        assert_eq!((lru_cache.expired_count(), lru_cache.live_count()), (2, 1));
+        // 只统计，不会淘汰
+        assert_eq!(lru_cache.list.len(), 3);
+
+        assert_eq!(lru_cache.sweep(), Ok(2));
+        assert_eq!((lru_cache.expired_count(), lru_cache.live_count()), (0, 1));
+    }
+
+    #[test]
+    fn lru_cache_query_keeps_index() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(8);
+        for ele in 0..8 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        let indices = lru_cache.map.clone();
+        for round in 0..1000 {
+            let key = (round * 5 + round / 8) % 8;
+            assert_eq!(lru_cache.query(&key), Ok(&key));
+        }
+        // query 不会重新分配内存单位，map 中的 index 保持不变且仍然有效
+        assert_eq!(lru_cache.map, indices);
+        for (key, index) in &lru_cache.map {
+            assert_eq!(lru_cache.list.get(index).unwrap().value.key, *key);
+        }
+        assert_eq!(lru_cache.keys().count(), 8);
+    }
+
+    // 读路径的基准测试，比较移动到头部的三种方式，默认不运行：
+    // cargo test --release --lib lru_cache_bench_query_reorder -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn lru_cache_bench_query_reorder() {
+        const ENTRIES: i32 = 10_000;
+        const QUERIES: u32 = 2_000_000;
+        const RUNS: usize = 7;
+
+        let build = || {
+            let mut lru_cache = Cache::<i32, i32>::new_with_cap(ENTRIES as usize);
+            for ele in 0..ENTRIES {
+                lru_cache.insert(ele, ele).unwrap();
+            }
+            lru_cache
+        };
+        // 固定种子的线性同余序列，保证三种方式访问的 key 相同
+        let keys = || {
+            let mut state = 0x2545_f491_u64;
+            (0..QUERIES).map(move |_| {
+                state = state
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1);
+                ((state >> 33) % ENTRIES as u64) as i32
+            })
+        };
+        let bench = |name: &str, f: &dyn Fn(&mut Cache<i32, i32>, i32)| {
+            let best = (0..RUNS)
+                .map(|_| {
+                    let mut lru_cache = build();
+                    let start = time::Instant::now();
+                    for key in keys() {
+                        f(&mut lru_cache, key);
+                    }
+                    start.elapsed()
+                })
+                .min()
+                .unwrap();
+            println!(
+                "{name:<36}{:>8.1} ns/query",
+                best.as_nanos() as f64 / QUERIES as f64
+            );
+        };
+
+        bench("remove + push_front, with map write", &|lru_cache, key| {
+            let index = lru_cache.map[&key];
+            let index = lru_cache.list.reposition_to_head(&index).unwrap();
+            lru_cache.map.insert(key, index);
+        });
+        bench("in-place move, with map write", &|lru_cache, key| {
+            let index = lru_cache.map[&key];
+            lru_cache.list.move_to_head(&index).unwrap();
+            lru_cache.map.insert(key, index);
+        });
+        bench("in-place move, without map write", &|lru_cache, key| {
+            lru_cache.query(&key).unwrap();
+        });
+    }
+
+    #[test]
+    fn lru_cache_insert_unwrap() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(1);
+        assert_eq!(lru_cache.insert_unwrap(0, 0), None);
+        assert_eq!(lru_cache.insert_unwrap(0, 1), Some(0));
+        assert_eq!(lru_cache.query(&0), Ok(&1));
+    }
+
+    #[test]
+    #[should_panic(expected = "failed to insert into cache")]
+    fn lru_cache_insert_unwrap_panics() {
+        let mut lru_cache = Cache::<i32, i32>::new();
+        lru_cache.insert_unwrap(0, 0);
+    }
+
+    #[test]
+    fn lru_cache_new() {
+        let mut lru_cache = Cache::<i32, i32>::new();
+        assert_eq!(lru_cache.capacity(), 0);
+        assert_eq!(lru_cache.insert(0, 0), Err(CacheError::ZeroCapacity));
+        assert_eq!(
+            CacheError::ZeroCapacity.to_string(),
+            "Cache has zero capacity; reserve capacity before inserting."
+        );
+
+        lru_cache.reserve(2);
+        assert_eq!(lru_cache.insert(0, 0), Ok(None));
+        assert_eq!(lru_cache.insert(1, 1), Ok(None));
+        assert_eq!(lru_cache.query(&0), Ok(&0));
+        assert_eq!(lru_cache.len(), 2);
+
+        let lru_cache: Cache<i32, i32> = Default::default();
+        assert_eq!(lru_cache.capacity(), 0);
+        assert!(lru_cache.is_empty());
+    }
+
+    #[test]
+    fn lru_cache_resize() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(3);
+        // [2-2 1-1 0-0]
+        for ele in 0..3 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+
+        // 扩大容量后可以容纳更多数据，不会淘汰
+        lru_cache.resize(5).unwrap();
+        assert_eq!(lru_cache.capacity(), 5);
+        assert_eq!(lru_cache.list.capacity(), 5);
+        for ele in 3..5 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert_eq!(lru_cache.len(), 5);
+        assert_eq!(lru_cache.total_evicted(), 0);
+
+        // 缩小容量时从尾部淘汰 [4-4 3-3]
+        lru_cache.resize(2).unwrap();
+        assert_eq!(lru_cache.capacity(), 2);
+        assert!(lru_cache.keys().eq(&[4, 3]));
+        assert_eq!(lru_cache.map.len(), 2);
+        assert_eq!(lru_cache.total_evicted(), 3);
+        assert_eq!(lru_cache.list.capacity(), 5);
+
+        // 缩小后按新的容量淘汰
+        lru_cache.insert(5, 5).unwrap();
+        assert!(lru_cache.keys().eq(&[5, 4]));
+
+        lru_cache.resize(0).unwrap();
+        assert!(lru_cache.is_empty());
+        assert!(lru_cache.map.is_empty());
+    }
+
+    #[test]
+    fn lru_cache_reclaim() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(8);
+        // [7-7 6-6 5-5 4-4 3-3 2-2 1-1 0-0]
+        for ele in 0..8 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        for ele in [0, 2, 3, 5, 7] {
+            lru_cache.remove(&ele).unwrap();
+        }
+        // [1-1 6-6 4-4]
+        lru_cache.query(&1).unwrap();
+
+        lru_cache.reclaim().unwrap();
+        assert_eq!(lru_cache.list.capacity(), 3);
+        assert_eq!(lru_cache.capacity(), 8);
+        assert!(lru_cache.keys().eq(&[1, 6, 4]));
+        for ele in [1, 4, 6] {
+            assert_eq!(lru_cache.peek(&ele), Ok(&ele));
+        }
+
+        // 底层内存按需扩容，达到容量后才开始淘汰
+        for ele in 10..15 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert_eq!(lru_cache.len(), 8);
+        assert_eq!(lru_cache.list.capacity(), 8);
+        assert_eq!(lru_cache.total_evicted(), 0);
+        lru_cache.insert(15, 15).unwrap();
+        assert_eq!(lru_cache.total_evicted(), 1);
+        assert_eq!(lru_cache.query(&4), Err(CacheError::CacheMiss));
+        assert_eq!(lru_cache.query(&6), Ok(&6));
+    }
+
+    #[test]
+    fn lru_cache_shrink_to_fit() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(1000);
+        for ele in 0..1000 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        for ele in 0..1000 {
+            if ele % 100 != 0 {
+                lru_cache.remove(&ele).unwrap();
+            }
+        }
+        lru_cache.query(&0).unwrap();
+
+        lru_cache.shrink_to_fit().unwrap();
+        assert_eq!(lru_cache.list.capacity(), 10);
+        assert_eq!(lru_cache.capacity(), 1000);
+        assert!(lru_cache.keys().copied().eq([0, 900, 800, 700, 600, 500, 400, 300, 200, 100]));
+        for ele in (0..1000).step_by(100) {
+            assert_eq!(lru_cache.query(&ele), Ok(&ele));
+        }
+
+        lru_cache.insert(1, 1).unwrap();
+        assert_eq!(lru_cache.len(), 11);
+    }
+
+    #[test]
+    fn lru_cache_expiration_policy() {
+        let clock = MockClock::new();
+        let build = |policy| {
+            Cache::<i32, i32>::builder()
+                .capacity(3)
+                .timeout(time::Duration::from_millis(200))
+                .expiration(policy)
+                .clock(Arc::new(clock.clone()))
+                .build()
+        };
+        let mut sliding = build(ExpirationPolicy::Sliding);
+        let mut fixed = build(ExpirationPolicy::Fixed);
+        for cache in [&mut sliding, &mut fixed] {
+            cache.insert(0, 0).unwrap();
+            cache.insert(1, 1).unwrap();
+        }
+
+        // 每 100ms 访问一次 0，共 300ms
+        for _ in 0..3 {
+            clock.advance(time::Duration::from_millis(100));
+            for cache in [&mut sliding, &mut fixed] {
+                let _ = cache.query(&0);
+            }
+        }
+        // Sliding：0 被访问后延长了淘汰时间，1 已过期
+        sliding.insert(2, 2).unwrap();
+        assert!(sliding.keys().eq(&[2, 0]));
+        // Fixed：访问不会延长淘汰时间，0 和 1 都已过期
+        fixed.insert(2, 2).unwrap();
+        assert!(fixed.keys().eq(&[2]));
+        assert_eq!(fixed.query(&0), Err(CacheError::CacheMiss));
+
+        // Fixed：query 会移动到头部但不改变淘汰时间，重新 insert 会重新计算
+        fixed.insert(3, 3).unwrap();
+        let expire_time =
+            |cache: &Cache<i32, i32>| cache.list.get(&cache.map[&2]).unwrap().expire_time();
+        let before = expire_time(&fixed);
+        clock.advance(time::Duration::from_millis(10));
+        fixed.query(&2).unwrap();
+        fixed.touch(&2).unwrap();
+        assert!(fixed.keys().eq(&[2, 3]));
+        assert_eq!(expire_time(&fixed), before);
+        fixed.insert(2, 20).unwrap();
+        assert!(expire_time(&fixed) > before);
+
+        // Fixed：被访问的 3 位于头部但先于尾部的 2 过期，len 和 sweep 同样不会遗漏
+        fixed.query(&3).unwrap();
+        assert!(fixed.keys().eq(&[3, 2]));
+        clock.advance(time::Duration::from_millis(190));
+        assert_eq!(fixed.len(), 1);
+        assert_eq!(fixed.sweep(), Ok(1));
+        assert!(fixed.keys().eq(&[2]));
+    }
+
+    #[test]
+    fn lru_cache_sweep() {
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(5)
+            .timeout(time::Duration::from_millis(100))
+            .clock(Arc::new(clock.clone()))
+            .build();
+        for ele in 0..3 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert_eq!(lru_cache.sweep(), Ok(0));
+
+        clock.advance(time::Duration::from_millis(50));
+        lru_cache.insert(3, 3).unwrap();
+        clock.advance(time::Duration::from_millis(50));
+        assert_eq!(lru_cache.sweep(), Ok(3));
+        assert!(lru_cache.keys().eq(&[3]));
+        assert_eq!(lru_cache.map.len(), 1);
+        assert_eq!(lru_cache.total_expired(), 3);
+        assert_eq!(lru_cache.sweep(), Ok(0));
+    }
+
+    #[test]
+    fn lru_cache_auto_retire() {
+        let clock = MockClock::new();
+        let mut lru_cache = Cache::<i32, i32>::builder()
+            .capacity(5)
+            .timeout(time::Duration::from_millis(100))
+            .clock(Arc::new(clock.clone()))
+            .build();
+        lru_cache.set_auto_retire(false);
+        for ele in 0..3 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        clock.advance(time::Duration::from_millis(100));
+
+        // 过期数据不会在 insert 时被淘汰
+        lru_cache.insert(3, 3).unwrap();
+        assert!(lru_cache.keys().eq(&[3, 2, 1, 0]));
+        assert_eq!(lru_cache.peek(&0), Ok(&0));
+        assert_eq!(lru_cache.total_expired(), 0);
+
+        assert_eq!(lru_cache.sweep(), Ok(3));
+        assert!(lru_cache.keys().eq(&[3]));
+        assert_eq!(lru_cache.map.len(), 1);
+
+        // 重新开启后恢复默认行为
+        lru_cache.set_auto_retire(true);
+        clock.advance(time::Duration::from_millis(100));
+        lru_cache.insert(4, 4).unwrap();
+        assert!(lru_cache.keys().eq(&[4]));
+    }
+
+    #[test]
+    fn lru_cache_get_mut() {
+        let mut lru_cache = Cache::<i32, Vec<i32>>::new_with_cap(2);
+        lru_cache.insert(1, vec![1]).unwrap();
+        lru_cache.insert(2, vec![2]).unwrap();
+
+        // [1-[1, 10] 2-[2]]
+        lru_cache.get_mut(&1).unwrap().push(10);
+        assert!(lru_cache.keys().eq(&[1, 2]));
+        assert_eq!(lru_cache.query(&1), Ok(&vec![1, 10]));
+        assert_eq!(lru_cache.get_mut(&3), Err(CacheError::CacheMiss));
+
+        // 2 是最久未访问的数据
+        lru_cache.insert(3, vec![3]).unwrap();
+        assert_eq!(lru_cache.query(&2), Err(CacheError::CacheMiss));
+        assert_eq!(lru_cache.stats().hits, 2);
+    }
+
+    #[test]
+    fn lru_cache_get_many_insert_many() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(4);
+        // [3-3 2-2 1-1 0-0]
+        lru_cache.insert_many((0..4).map(|ele| (ele, ele)).collect()).unwrap();
+        assert!(lru_cache.keys().eq(&[3, 2, 1, 0]));
+
+        // [1-1 0-0 3-3 2-2]
+        let values = lru_cache.get_many(&[0, 5, 1]);
+        assert_eq!(values, vec![Ok(0), Err(CacheError::CacheMiss), Ok(1)]);
+        assert!(lru_cache.keys().eq(&[1, 0, 3, 2]));
+
+        // 超过容量时靠前的数据被淘汰
+        lru_cache.insert_many(vec![(4, 4), (5, 5), (0, 10)]).unwrap();
+        assert!(lru_cache.keys().eq(&[0, 5, 4, 1]));
+        assert_eq!(lru_cache.get_many(&[0, 2]), vec![Ok(10), Err(CacheError::CacheMiss)]);
+
+        lru_cache.set_max_value_size(100, |value| *value as usize);
+        assert_eq!(
+            lru_cache.insert_many(vec![(6, 6), (7, 1000), (8, 8)]),
+            Err(CacheError::ValueTooLarge { size: 1000, max: 100 })
+        );
+        assert_eq!(lru_cache.peek(&6), Ok(&6));
+        assert_eq!(lru_cache.peek(&8), Err(CacheError::CacheMiss));
+    }
+
+    #[test]
+    fn lru_cache_entry() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(3);
+        // [2-2 1-1 0-0]
+        for ele in 0..3 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+
+        // Occupied + or_insert：不会覆盖，移动到头部 [0-0 2-2 1-1]
+        assert_eq!(lru_cache.entry(0).unwrap().or_insert(-1), Ok(&mut 0));
+        assert!(lru_cache.keys().eq(&[0, 2, 1]));
+
+        // Occupied + and_modify [1-11 0-0 2-2]
+        let value = lru_cache
+            .entry(1)
+            .unwrap()
+            .and_modify(|value| *value += 10)
+            .or_insert(-1);
+        assert_eq!(value, Ok(&mut 11));
+        assert!(lru_cache.keys().eq(&[1, 0, 2]));
+
+        // Vacant + and_modify + or_insert_with：按照容量淘汰 [3-3 1-11 0-0]
+        let mut calls = 0;
+        let value = lru_cache
+            .entry(3)
+            .unwrap()
+            .and_modify(|value| *value += 10)
+            .or_insert_with(|| {
+                calls += 1;
+                3
+            });
+        assert_eq!(value, Ok(&mut 3));
+        assert_eq!(calls, 1);
+        assert!(lru_cache.keys().eq(&[3, 1, 0]));
+        assert_eq!(lru_cache.query(&2), Err(CacheError::CacheMiss));
+
+        // Occupied + or_insert_with：不会调用 f
+        let value = lru_cache.entry(0).unwrap().or_insert_with(|| {
+            calls += 1;
+            -1
+        });
+        *value.unwrap() += 5;
+        assert_eq!(calls, 1);
+        assert_eq!(lru_cache.peek(&0), Ok(&5));
+
+        // Vacant + or_insert
+        assert_eq!(lru_cache.entry(4).unwrap().or_insert(4), Ok(&mut 4));
+        assert!(lru_cache.keys().eq(&[4, 0, 3]));
+
+        match lru_cache.entry(3).unwrap() {
+            Entry::Occupied(entry) => assert_eq!(entry.remove(), Ok(3)),
+            Entry::Vacant(_) => panic!("3 should be occupied"),
+        }
+        assert!(matches!(lru_cache.entry(3).unwrap(), Entry::Vacant(_)));
+        assert_eq!(lru_cache.stats().hits, 0);
+    }
+
+    #[test]
+    fn lru_cache_index() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(3);
+        // [2-2 1-1 0-0]
+        for ele in 0..3 {
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert_eq!(lru_cache[&0], 0);
+        lru_cache[&1] += 10;
+        assert_eq!(lru_cache[&1], 11);
+        // 索引不会改变访问顺序
+        assert!(lru_cache.keys().eq(&[2, 1, 0]));
+        assert_eq!(lru_cache.stats().hits, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "key not found in cache")]
+    fn lru_cache_index_missing() {
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(3);
+        lru_cache.insert(0, 0).unwrap();
+        let _ = lru_cache[&1];
     }
 }
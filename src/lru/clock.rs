@@ -0,0 +1,83 @@
+use std::sync::{Arc, Mutex};
+use std::time;
+
+// 超时淘汰使用的时间来源
+// LinkedList 和 Cache 通过它获取当前时间，测试时可以替换为 MockClock 手动推进时间
+pub trait Clock: Send + Sync {
+    fn now(&self) -> time::Instant;
+
+    // 将当前时间设置为 instant，返回是否设置成功
+    // 默认不支持设置（例如 SystemClock），MockClock 支持
+    fn set_now(&self, _instant: time::Instant) -> bool {
+        false
+    }
+}
+
+// 系统时间，默认的时间来源
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> time::Instant {
+        time::Instant::now()
+    }
+}
+
+// 只在调用 advance 时前进的时间，初始值为创建时的系统时间
+// clone 得到的 MockClock 共享同一个时间，因此交给缓存之后仍然可以推进
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Arc<Mutex<time::Instant>>,
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        MockClock {
+            now: Arc::new(Mutex::new(time::Instant::now())),
+        }
+    }
+
+    // 将时间向前推进 duration
+    pub fn advance(&self, duration: time::Duration) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) += duration;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> time::Instant {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn set_now(&self, instant: time::Instant) -> bool {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) = instant;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clock_mock_advance() {
+        let clock = MockClock::new();
+        let shared = clock.clone();
+        let start = clock.now();
+        assert_eq!(clock.now(), start);
+
+        shared.advance(time::Duration::from_secs(5));
+        assert_eq!(clock.now() - start, time::Duration::from_secs(5));
+        assert!(SystemClock.now() < clock.now());
+
+        let later = start + time::Duration::from_secs(10);
+        assert!(shared.set_now(later));
+        assert_eq!(clock.now(), later);
+        assert!(!SystemClock.set_now(later));
+    }
+}
@@ -1,4 +1,7 @@
-use std::fmt::{Display, Formatter, Result};
+use core::error::Error;
+use core::fmt::{Display, Formatter, Result};
+
+use crate::lru::arena::Index;
 
 #[derive(PartialEq, Debug, Clone)]
 pub struct ArenaOOM;
@@ -9,40 +12,151 @@ impl Display for ArenaOOM {
     }
 }
 
+impl Error for ArenaOOM {}
+
 #[derive(PartialEq, Debug, Clone)]
 pub enum ListError {
-    LinkBroken,
+    // 无效的 Index（已被删除或者代数不匹配）
+    LinkBroken { idx: usize, generation: u64 },
     ListOOM(ArenaOOM),
     ListEmpty,
+    // 容量为 0 时插入，需要先扩容
+    ZeroCapacity,
 }
 
 impl Display for ListError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         match &self {
-            ListError::LinkBroken => write!(f, "Link does not point to a valid location."),
+            ListError::LinkBroken { idx, generation } => write!(
+                f,
+                "Link does not point to a valid location (index {idx}, generation {generation})."
+            ),
             ListError::ListOOM(arena_oom) => {
                 write!(f, "List out of memory: ")?;
                 arena_oom.fmt(f)
             }
             ListError::ListEmpty => write!(f, "List is empty."),
+            ListError::ZeroCapacity => {
+                write!(
+                    f,
+                    "List has zero capacity; reserve capacity before inserting."
+                )
+            }
+        }
+    }
+}
+
+impl ListError {
+    // index 指向的位置无效
+    pub fn link_broken(index: &Index) -> Self {
+        ListError::LinkBroken {
+            idx: index.idx(),
+            generation: index.generation(),
+        }
+    }
+}
+
+impl Error for ListError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ListError::ListOOM(arena_oom) => Some(arena_oom),
+            _ => None,
         }
     }
 }
 
+impl From<ArenaOOM> for ListError {
+    fn from(arena_oom: ArenaOOM) -> Self {
+        ListError::ListOOM(arena_oom)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum CacheError {
     CacheBroken(ListError),
     CacheMiss,
+    ValueTooLarge { size: usize, max: usize },
+    // Reject 策略下缓存已满，插入需要 needed 的容量（个数或权重），但只剩 available
+    CapacityExceeded { needed: usize, available: usize },
+    // 开启准入策略后，新 key 的访问频率不高于即将被淘汰的数据，没有被插入
+    NotAdmitted,
+    // 缓存容量为 0 时插入，需要先扩容，并不表示缓存损坏
+    ZeroCapacity,
 }
 
 impl Display for CacheError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         match &self {
             CacheError::CacheBroken(list_error) => {
                 write!(f, "Cache storage is broken: ")?;
                 list_error.fmt(f)
             }
             CacheError::CacheMiss => write!(f, "Key not found in cache."),
+            CacheError::ValueTooLarge { size, max } => {
+                write!(f, "Value size {size} exceeds the limit {max}.")
+            }
+            CacheError::CapacityExceeded { needed, available } => {
+                write!(f, "Cache is full: needed {needed}, available {available}.")
+            }
+            CacheError::NotAdmitted => write!(f, "Key was rejected by the admission filter."),
+            CacheError::ZeroCapacity => write!(
+                f,
+                "Cache has zero capacity; reserve capacity before inserting."
+            ),
         }
     }
-}
\ No newline at end of file
+}
+
+impl Error for CacheError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CacheError::CacheBroken(list_error) => Some(list_error),
+            _ => None,
+        }
+    }
+}
+
+// 链表容量为 0 说明缓存容量为 0，单独作为 ZeroCapacity 返回
+impl From<ListError> for CacheError {
+    fn from(list_error: ListError) -> Self {
+        match list_error {
+            ListError::ZeroCapacity => CacheError::ZeroCapacity,
+            list_error => CacheError::CacheBroken(list_error),
+        }
+    }
+}
+
+impl From<ArenaOOM> for CacheError {
+    fn from(arena_oom: ArenaOOM) -> Self {
+        CacheError::CacheBroken(ListError::ListOOM(arena_oom))
+    }
+}
+
+// 计算 value 可能失败的操作返回的错误：缓存本身的错误或调用方计算 value 时的错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum CacheOrUserError<E> {
+    Cache(CacheError),
+    User(E),
+}
+
+impl<E: Display> Display for CacheOrUserError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match &self {
+            CacheOrUserError::Cache(cache_error) => cache_error.fmt(f),
+            CacheOrUserError::User(user_error) => {
+                write!(f, "Failed to compute value: ")?;
+                user_error.fmt(f)
+            }
+        }
+    }
+}
+
+// Cache 的 Display 与 CacheError 相同，因此 source 也与 CacheError 相同
+impl<E: Error + 'static> Error for CacheOrUserError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CacheOrUserError::Cache(cache_error) => cache_error.source(),
+            CacheOrUserError::User(user_error) => Some(user_error),
+        }
+    }
+}
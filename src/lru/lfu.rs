@@ -0,0 +1,357 @@
+use core::hash::Hash;
+use core::mem;
+
+use crate::lru::arena::{Arena, Index};
+use crate::lru::err::{CacheError, ListError};
+use crate::lru::HashMap;
+
+struct LfuNode<K, V> {
+    key: K,
+    value: V,
+    // 被访问的次数，插入时为 1
+    freq: u64,
+    // 同一访问频率的链表中的前后节点，头部是最近访问的
+    prev: Option<Index>,
+    next: Option<Index>,
+}
+
+// 某个访问频率对应的链表的头尾
+struct Bucket {
+    head: Index,
+    tail: Index,
+}
+
+// 按照访问频率淘汰的缓存：容量已满时淘汰访问次数最少的数据，次数相同时淘汰最久未访问的
+// 所有数据存放在同一个 Arena 中，每个访问频率各自串成一个双向链表
+// 因此查询、插入和淘汰都是 O(1) 的
+// 不带有超时淘汰机制
+pub struct LfuCache<K, V>
+where
+    K: Eq + Hash,
+{
+    arena: Arena<LfuNode<K, V>>,
+    map: HashMap<K, Index>,
+    buckets: HashMap<u64, Bucket>,
+    // 当前最小的访问频率，remove 之后可能失效，淘汰时会重新计算
+    min_freq: u64,
+    cap: usize,
+}
+
+impl<K, V> LfuCache<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    pub fn new_with_cap(cap: usize) -> Self {
+        LfuCache {
+            arena: Arena::new_with_cap(cap),
+            map: HashMap::with_capacity(cap),
+            buckets: HashMap::new(),
+            min_freq: 0,
+            cap,
+        }
+    }
+
+    // 查询 key 对应的 value，访问次数加 1
+    pub fn query(&mut self, key: &K) -> Result<&V, CacheError> {
+        let index = *self.map.get(key).ok_or(CacheError::CacheMiss)?;
+        self.bump(&index)?;
+        Ok(&self.node(&index)?.value)
+    }
+
+    // 查询 key 对应的 value，不改变访问次数
+    pub fn peek(&self, key: &K) -> Result<&V, CacheError> {
+        let index = self.map.get(key).ok_or(CacheError::CacheMiss)?;
+        Ok(&self.node(index)?.value)
+    }
+
+    // key 被访问的次数
+    pub fn frequency(&self, key: &K) -> Result<u64, CacheError> {
+        let index = self.map.get(key).ok_or(CacheError::CacheMiss)?;
+        Ok(self.node(index)?.freq)
+    }
+
+    // 插入 key-value，key 已存在时覆盖并返回旧的 value，覆盖视为一次访问
+    // 容量已满时先淘汰访问次数最少的数据
+    pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>, CacheError> {
+        self.insert_with_frequency(key, value, 1)
+    }
+
+    // 与 insert 相同，但新数据的访问次数从 freq（至少为 1）开始，用于从其它缓存迁移数据
+    pub(crate) fn insert_with_frequency(
+        &mut self,
+        key: K,
+        value: V,
+        freq: u64,
+    ) -> Result<Option<V>, CacheError> {
+        if let Some(index) = self.map.get(&key).copied() {
+            self.bump(&index)?;
+            let node = self.node_mut(&index)?;
+            return Ok(Some(mem::replace(&mut node.value, value)));
+        }
+
+        if self.cap == 0 {
+            return Err(CacheError::ZeroCapacity);
+        }
+        if self.map.len() >= self.cap {
+            self.evict()?;
+        }
+        let node = LfuNode {
+            key: key.clone(),
+            value,
+            freq: freq.max(1),
+            prev: None,
+            next: None,
+        };
+        let index = self.arena.insert(node)?;
+        self.attach(&index)?;
+        self.min_freq = if self.map.is_empty() {
+            freq.max(1)
+        } else {
+            self.min_freq.min(freq.max(1))
+        };
+        self.map.insert(key, index);
+        Ok(None)
+    }
+
+    pub fn remove(&mut self, key: &K) -> Result<V, CacheError> {
+        let index = self.map.remove(key).ok_or(CacheError::CacheMiss)?;
+        self.detach(&index)?;
+        let node = self.arena.remove(&index).ok_or_else(|| broken(&index))?;
+        Ok(node.value)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    // 淘汰访问次数最少的数据中最久未访问的一个（链表尾部）
+    fn evict(&mut self) -> Result<(), CacheError> {
+        let victim = match self.buckets.get(&self.min_freq) {
+            Some(bucket) => bucket.tail,
+            None => {
+                // min_freq 在 remove 之后可能失效，重新计算
+                let (freq, bucket) = match self.buckets.iter().min_by_key(|(freq, _)| **freq) {
+                    Some(entry) => entry,
+                    // 没有任何频率链表却仍有数据，说明这些数据的链接已经丢失
+                    None => {
+                        return self
+                            .map
+                            .values()
+                            .next()
+                            .map_or(Ok(()), |index| Err(broken(index)))
+                    }
+                };
+                self.min_freq = *freq;
+                bucket.tail
+            }
+        };
+        self.detach(&victim)?;
+        let node = self.arena.remove(&victim).ok_or_else(|| broken(&victim))?;
+        self.map.remove(&node.key);
+        Ok(())
+    }
+
+    // 访问次数加 1，并移动到新频率链表的头部
+    fn bump(&mut self, index: &Index) -> Result<(), CacheError> {
+        let freq = self.node(index)?.freq;
+        self.detach(index)?;
+        if freq == self.min_freq && !self.buckets.contains_key(&freq) {
+            self.min_freq = freq + 1;
+        }
+        self.node_mut(index)?.freq = freq.saturating_add(1);
+        self.attach(index)
+    }
+
+    // 插入到所在频率链表的头部
+    fn attach(&mut self, index: &Index) -> Result<(), CacheError> {
+        let freq = self.node(index)?.freq;
+        let old_head = match self.buckets.get_mut(&freq) {
+            Some(bucket) => mem::replace(&mut bucket.head, *index),
+            None => {
+                self.buckets.insert(
+                    freq,
+                    Bucket {
+                        head: *index,
+                        tail: *index,
+                    },
+                );
+                return Ok(());
+            }
+        };
+        self.node_mut(&old_head)?.prev = Some(*index);
+        self.node_mut(index)?.next = Some(old_head);
+        Ok(())
+    }
+
+    // 从所在频率的链表中摘除，链表为空时删除该频率
+    fn detach(&mut self, index: &Index) -> Result<(), CacheError> {
+        let node = self.node_mut(index)?;
+        let (freq, prev, next) = (node.freq, node.prev.take(), node.next.take());
+
+        match prev {
+            Some(prev) => self.node_mut(&prev)?.next = next,
+            None => {
+                if let Some(next) = next {
+                    self.buckets
+                        .get_mut(&freq)
+                        .ok_or_else(|| broken(index))?
+                        .head = next;
+                }
+            }
+        }
+        match next {
+            Some(next) => self.node_mut(&next)?.prev = prev,
+            None => {
+                if let Some(prev) = prev {
+                    self.buckets
+                        .get_mut(&freq)
+                        .ok_or_else(|| broken(index))?
+                        .tail = prev;
+                }
+            }
+        }
+        if prev.is_none() && next.is_none() {
+            self.buckets.remove(&freq);
+        }
+        Ok(())
+    }
+
+    fn node(&self, index: &Index) -> Result<&LfuNode<K, V>, CacheError> {
+        self.arena.get(index).ok_or_else(|| broken(index))
+    }
+
+    fn node_mut(&mut self, index: &Index) -> Result<&mut LfuNode<K, V>, CacheError> {
+        self.arena.get_mut(index).ok_or_else(|| broken(index))
+    }
+}
+
+fn broken(index: &Index) -> CacheError {
+    CacheError::CacheBroken(ListError::link_broken(index))
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::lru::cache::Cache;
+
+    use super::*;
+
+    #[test]
+    fn lfu_cache_basic() {
+        let mut lfu_cache = LfuCache::<i32, &str>::new_with_cap(2);
+        assert_eq!(lfu_cache.insert(1, "a"), Ok(None));
+        assert_eq!(lfu_cache.insert(2, "b"), Ok(None));
+        assert_eq!(lfu_cache.query(&1), Ok(&"a"));
+        assert_eq!(lfu_cache.frequency(&1), Ok(2));
+        assert_eq!(lfu_cache.frequency(&2), Ok(1));
+
+        // 2 的访问次数最少，被淘汰
+        assert_eq!(lfu_cache.insert(3, "c"), Ok(None));
+        assert!(!lfu_cache.contains_key(&2));
+        assert_eq!(lfu_cache.len(), 2);
+
+        // 覆盖视为一次访问
+        assert_eq!(lfu_cache.insert(3, "d"), Ok(Some("c")));
+        assert_eq!(lfu_cache.frequency(&3), Ok(2));
+        assert_eq!(lfu_cache.peek(&3), Ok(&"d"));
+        assert_eq!(lfu_cache.frequency(&3), Ok(2));
+
+        assert_eq!(lfu_cache.remove(&1), Ok("a"));
+        assert_eq!(lfu_cache.remove(&1), Err(CacheError::CacheMiss));
+        assert_eq!(lfu_cache.query(&2), Err(CacheError::CacheMiss));
+        assert_eq!(lfu_cache.len(), 1);
+
+        let mut lfu_cache = LfuCache::<i32, &str>::new_with_cap(0);
+        assert_eq!(lfu_cache.insert(1, "a"), Err(CacheError::ZeroCapacity));
+    }
+
+    #[test]
+    fn lfu_cache_tie_breaks_by_recency() {
+        let mut lfu_cache = LfuCache::<i32, i32>::new_with_cap(3);
+        for ele in 0..3 {
+            lfu_cache.insert(ele, ele).unwrap();
+        }
+        for ele in [1, 0, 2, 1, 0] {
+            lfu_cache.query(&ele).unwrap();
+        }
+        // 访问次数：0 -> 3, 1 -> 3, 2 -> 2
+        lfu_cache.insert(3, 3).unwrap();
+        assert!(!lfu_cache.contains_key(&2));
+
+        lfu_cache.query(&3).unwrap();
+        lfu_cache.query(&3).unwrap();
+        // 0、1、3 的访问次数都为 3，淘汰最久未访问的 1
+        lfu_cache.insert(4, 4).unwrap();
+        assert!(!lfu_cache.contains_key(&1));
+        assert!(lfu_cache.contains_key(&0));
+        assert!(lfu_cache.contains_key(&3));
+    }
+
+    #[test]
+    fn lfu_cache_hot_key_survives() {
+        let mut lfu_cache = LfuCache::<i32, i32>::new_with_cap(3);
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(3);
+        for _ in 0..5 {
+            lfu_cache.insert(0, 0).unwrap();
+            lru_cache.insert(0, 0).unwrap();
+        }
+
+        // 一轮只访问一次的 key 会让 LRU 淘汰热点 key
+        for ele in 1..10 {
+            lfu_cache.insert(ele, ele).unwrap();
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert!(!lru_cache.contains_key(&0));
+        assert_eq!(lfu_cache.query(&0), Ok(&0));
+        assert_eq!(lfu_cache.len(), 3);
+        assert!(lfu_cache.contains_key(&9));
+    }
+
+    #[test]
+    fn lfu_cache_missing_bucket() {
+        let mut lfu_cache = LfuCache::<i32, i32>::new_with_cap(1);
+        lfu_cache.insert(0, 0).unwrap();
+        let index = *lfu_cache.map.get(&0).unwrap();
+        // 频率链表丢失视为链接损坏，而不是链表为空
+        lfu_cache.buckets.clear();
+        assert_eq!(
+            lfu_cache.insert(1, 1),
+            Err(CacheError::CacheBroken(ListError::link_broken(&index)))
+        );
+    }
+
+    #[test]
+    fn lfu_cache_remove_min_frequency() {
+        let mut lfu_cache = LfuCache::<i32, i32>::new_with_cap(2);
+        lfu_cache.insert(0, 0).unwrap();
+        lfu_cache.insert(1, 1).unwrap();
+        lfu_cache.query(&0).unwrap();
+        lfu_cache.query(&1).unwrap();
+        lfu_cache.query(&1).unwrap();
+
+        // 删除后最小访问频率失效，淘汰时重新计算：0 -> 2, 2 -> 1
+        lfu_cache.remove(&1).unwrap();
+        lfu_cache.insert(2, 2).unwrap();
+        lfu_cache.query(&2).unwrap();
+        lfu_cache.query(&2).unwrap();
+        lfu_cache.insert(3, 3).unwrap();
+        assert!(!lfu_cache.contains_key(&0));
+        assert!(lfu_cache.contains_key(&2));
+        assert!(lfu_cache.contains_key(&3));
+
+        let mut lfu_cache = LfuCache::<i32, i32>::new_with_cap(0);
+        assert!(lfu_cache.insert(0, 0).is_err());
+        assert!(lfu_cache.is_empty());
+    }
+}
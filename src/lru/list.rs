@@ -1,19 +1,52 @@
 use super::{
-    arena::{Arena, Index},
+    arena::{Arena, Index, RawArena},
     err::ListError,
+    HashMap,
 };
-
+#[cfg(feature = "std")]
+use super::clock::{Clock, SystemClock};
+
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::sync::Arc;
+#[cfg(feature = "std")]
 use std::time;
 
+#[derive(Clone)]
 pub struct Node<T> {
     pub value: T,
     // 淘汰时间
     // 为空说明没有开启自动超时淘汰机制
+    #[cfg(feature = "std")]
     expire_time: Option<time::Instant>,
+    // 过期后仍可以返回旧值的截止时间，即 expire_time + grace
+    // 超过该时间后节点才会被淘汰
+    #[cfg(feature = "std")]
+    stale_time: Option<time::Instant>,
+    // 节点自身的存活时间，移动到头部时据此重新计算淘汰时间
+    // 为 None 说明淘汰时间不随访问变化（没有淘汰时间，或者单独设置了绝对的淘汰时间）
+    #[cfg(feature = "std")]
+    ttl: Option<time::Duration>,
+    // 最近一次被插入或移动到头部的时间
+    #[cfg(feature = "std")]
+    last_access: time::Instant,
     next: Option<Index>,
     prev: Option<Index>,
 }
 
+// 节点被访问（移动到头部）时淘汰时间的计算方式
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExpirationPolicy {
+    // 每次访问都按照 timeout 重新计算淘汰时间
+    #[default]
+    Sliding,
+    // 淘汰时间在插入时确定，之后的访问不会延长
+    Fixed,
+}
+
+#[derive(Clone)]
 pub struct LinkedList<T> {
     arena: Arena<Node<T>>,
     head: Option<Index>,
@@ -21,7 +54,53 @@ pub struct LinkedList<T> {
     len: usize,
     // 每个节点 timeout 的时间
     // timeout 为 None 说明没有开启自动超时淘汰机制
+    #[cfg(feature = "std")]
     timeout: Option<time::Duration>,
+    // 节点过期后的宽限时间，宽限期内节点不会被淘汰
+    #[cfg(feature = "std")]
+    grace: Option<time::Duration>,
+    // 移动到头部时是否重新计算淘汰时间
+    #[cfg(feature = "std")]
+    policy: ExpirationPolicy,
+    // 计算淘汰时间和访问时间使用的时间来源
+    #[cfg(feature = "std")]
+    clock: Arc<dyn Clock>,
+    // 节点超过该时间没有被访问（移动到头部）也会被淘汰，与 timeout 相互独立
+    // 为 None 说明没有开启空闲淘汰
+    #[cfg(feature = "std")]
+    max_idle: Option<time::Duration>,
+    // 单独设置过淘汰时间后，节点的宽限截止时间可能不再从尾到头递增
+    // 此时 retire 需要遍历整个链表，直到某次遍历发现顺序恢复
+    #[cfg(feature = "std")]
+    unordered: bool,
+}
+
+#[cfg(feature = "std")]
+impl<T> Node<T> {
+    pub fn expire_time(&self) -> Option<time::Instant> {
+        self.expire_time
+    }
+
+    pub fn last_access(&self) -> time::Instant {
+        self.last_access
+    }
+
+    pub fn stale_time(&self) -> Option<time::Instant> {
+        self.stale_time
+    }
+}
+
+impl<T> Default for LinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 按照从头到尾的顺序输出节点的值
+impl<T: fmt::Debug> fmt::Debug for LinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
 }
 
 impl<T> LinkedList<T> {
@@ -31,11 +110,23 @@ impl<T> LinkedList<T> {
             head: None,
             tail: None,
             len: 0,
+            #[cfg(feature = "std")]
             timeout: None,
+            #[cfg(feature = "std")]
+            grace: None,
+            #[cfg(feature = "std")]
+            policy: ExpirationPolicy::Sliding,
+            #[cfg(feature = "std")]
+            clock: Arc::new(SystemClock),
+            #[cfg(feature = "std")]
+            max_idle: None,
+            #[cfg(feature = "std")]
+            unordered: false,
         }
     }
 
     // 从内存中创建一定容量的链表，并带有超时淘汰机制
+    #[cfg(feature = "std")]
     pub fn new_with_cap_timeout(cap: usize, timeout: time::Duration) -> Self {
         let mut list = LinkedList::new();
         list.reserve(cap);
@@ -50,29 +141,150 @@ impl<T> LinkedList<T> {
         list
     }
     
+    // 设置节点过期后的宽限时间，之后插入或移动的节点生效
+    #[cfg(feature = "std")]
+    pub fn set_grace(&mut self, grace: time::Duration) {
+        self.grace = Some(grace);
+    }
+
+    // 设置节点移动到头部时淘汰时间的计算方式，之后移动的节点生效
+    #[cfg(feature = "std")]
+    pub fn set_expiration_policy(&mut self, policy: ExpirationPolicy) {
+        self.policy = policy;
+    }
+
+    #[cfg(feature = "std")]
+    pub fn expiration_policy(&self) -> ExpirationPolicy {
+        self.policy
+    }
+
+    #[cfg(feature = "std")]
+    pub fn timeout(&self) -> Option<time::Duration> {
+        self.timeout
+    }
+
+    #[cfg(feature = "std")]
+    pub fn grace(&self) -> Option<time::Duration> {
+        self.grace
+    }
+
+    // 设置空闲淘汰时间，对所有节点（包括已有节点）生效
+    #[cfg(feature = "std")]
+    pub fn set_max_idle(&mut self, max_idle: time::Duration) {
+        self.max_idle = Some(max_idle);
+    }
+
+    #[cfg(feature = "std")]
+    pub fn max_idle(&self) -> Option<time::Duration> {
+        self.max_idle
+    }
+
+    // 以 now 作为当前时间，节点是否应当被淘汰：超过宽限截止时间，或者空闲时间超过 max_idle
+    #[cfg(feature = "std")]
+    pub(crate) fn is_retirable(&self, node: &Node<T>, now: time::Instant) -> bool {
+        node.stale_time.is_some_and(|stale_time| now >= stale_time)
+            || self
+                .max_idle
+                .is_some_and(|max_idle| now.saturating_duration_since(node.last_access) > max_idle)
+    }
+
+    // 替换时间来源，已有节点的淘汰时间不变
+    #[cfg(feature = "std")]
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    #[cfg(feature = "std")]
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
+    // 时间来源的当前时间
+    #[cfg(feature = "std")]
+    pub fn now(&self) -> time::Instant {
+        self.clock.now()
+    }
+
+    // 根据过期时间计算宽限截止时间，溢出时视为永不淘汰
+    #[cfg(feature = "std")]
+    fn stale_time_for(&self, expire_time: time::Instant) -> Option<time::Instant> {
+        expire_time.checked_add(self.grace.unwrap_or_default())
+    }
+
+    // 以 now 作为当前时间，按照存活时间 ttl 计算 (淘汰时间, 宽限截止时间)
+    // 溢出（例如 ttl 为 Duration::MAX）时视为永不过期
+    #[cfg(feature = "std")]
+    fn deadlines_for(
+        &self,
+        now: time::Instant,
+        ttl: Option<time::Duration>,
+    ) -> (Option<time::Instant>, Option<time::Instant>) {
+        let expire_time = ttl.and_then(|ttl| now.checked_add(ttl));
+        let stale_time = expire_time.and_then(|expire_time| self.stale_time_for(expire_time));
+        (expire_time, stale_time)
+    }
+
+    // index 节点的宽限截止时间与相邻节点相比是否仍然从尾到头递增（没有截止时间视为无穷大）
+    // 不满足时之后的 retire 需要遍历整个链表
+    #[cfg(feature = "std")]
+    fn check_order(&mut self, index: &Index) -> Result<(), ListError> {
+        let node = self.get(index)?;
+        let stale_time = node.stale_time;
+        // None 视为无穷大
+        let not_after =
+            |earlier: Option<time::Instant>, later: Option<time::Instant>| match (earlier, later) {
+                (_, None) => true,
+                (None, Some(_)) => false,
+                (Some(earlier), Some(later)) => earlier <= later,
+            };
+        let newer = node.prev.map(|prev| self.get(&prev)).transpose()?;
+        let older = node.next.map(|next| self.get(&next)).transpose()?;
+        if newer.is_some_and(|newer| !not_after(stale_time, newer.stale_time))
+            || older.is_some_and(|older| !not_after(older.stale_time, stale_time))
+        {
+            self.unordered = true;
+        }
+        Ok(())
+    }
+
     // 从内存中获取cap容量的内存
     pub fn reserve(&mut self, cap: usize) {
         self.arena.reserve(cap)
     }
 
+    // 与 reserve 相同，但内存申请失败时返回 ListOOM
+    pub fn try_reserve(&mut self, cap: usize) -> Result<(), ListError> {
+        self.arena.try_reserve(cap).map_err(ListError::ListOOM)
+    }
+
     // 头插并返回新节点的索引
+    // 容量为 0 时返回 ZeroCapacity
+    #[must_use = "the returned Index is needed to access the node later"]
     pub fn push_front(&mut self, value: T) -> Result<Index, ListError> {
-        let mut cur_head_node = Node {
+        if self.arena.cap() == 0 {
+            return Err(ListError::ZeroCapacity);
+        }
+        // 设置节点淘汰时间
+        #[cfg(feature = "std")]
+        let now = self.now();
+        #[cfg(feature = "std")]
+        let (expire_time, stale_time) = self.deadlines_for(now, self.timeout);
+        let cur_head_node = Node {
             value,
-            expire_time: None,
+            #[cfg(feature = "std")]
+            expire_time,
+            #[cfg(feature = "std")]
+            stale_time,
+            #[cfg(feature = "std")]
+            ttl: self.timeout,
+            #[cfg(feature = "std")]
+            last_access: now,
             next: self.head,
             prev: None,
         };
-        // 设置节点淘汰时间
-        if let Some(timeout) = self.timeout {
-            cur_head_node.expire_time = Some(time::Instant::now() + timeout);
-        }
 
         // 先找块空闲区域插入数据
-        let cur_head_index = self
-            .arena
-            .insert(cur_head_node)
-            .map_err(ListError::ListOOM)?;
+        let cur_head_index = self.arena.insert(cur_head_node)?;
 
         if let Some(old_head_index) = self.head {
             // 如果头节点不为空，则将其 prev 指向当前节点
@@ -87,19 +299,59 @@ impl<T> LinkedList<T> {
         // 转移头节点至 node
         self.head = Some(cur_head_index);
         self.len += 1;
+        // 宽限时间被缩短后，新节点的宽限截止时间可能早于之前的节点
+        #[cfg(feature = "std")]
+        self.check_order(&cur_head_index)?;
         Ok(cur_head_index)
     }
 
+    // 头插并将新节点的淘汰时间设置为绝对时间 expire_time，与链表的 timeout 无关
+    #[cfg(feature = "std")]
+    pub fn push_front_with_expiry(
+        &mut self,
+        value: T,
+        expire_time: time::Instant,
+    ) -> Result<Index, ListError> {
+        let index = self.push_front(value)?;
+        self.set_expire_time(&index, expire_time)?;
+        Ok(index)
+    }
+
+    // 头插并将新节点的存活时间设置为 ttl，与链表的 timeout 无关
+    // 与 push_front_with_expiry 不同，Sliding 策略下节点被移动到头部时按照 ttl 重新计算淘汰时间
+    #[cfg(feature = "std")]
+    pub fn push_front_with_ttl(
+        &mut self,
+        value: T,
+        ttl: time::Duration,
+    ) -> Result<Index, ListError> {
+        let index = self.push_front(value)?;
+        self.set_ttl(&index, Some(ttl))?;
+        Ok(index)
+    }
+
     // 从尾部插入节点
+    // 容量为 0 时返回 ZeroCapacity
+    #[must_use = "the returned Index is needed to access the node later"]
     pub fn push_back(&mut self, value: T) -> Result<Index, ListError> {
+        if self.arena.cap() == 0 {
+            return Err(ListError::ZeroCapacity);
+        }
         let node = Node {
             value,
             prev: self.tail,
             next: None,
+            #[cfg(feature = "std")]
             expire_time: None,
+            #[cfg(feature = "std")]
+            stale_time: None,
+            #[cfg(feature = "std")]
+            ttl: None,
+            #[cfg(feature = "std")]
+            last_access: self.now(),
         };
 
-        let index = self.arena.insert(node).map_err(ListError::ListOOM)?;
+        let index = self.arena.insert(node)?;
         let link = index;
         if let Some(tail) = self.tail {
             let tail_node = self.get_mut(&tail)?;
@@ -115,6 +367,7 @@ impl<T> LinkedList<T> {
     }
 
     // 头部删除节点
+    #[must_use = "pop_front returns the removed value or ListEmpty"]
     pub fn pop_front(&mut self) -> Result<T, ListError> {
         if let Some(head_index) = self.head {
             self.remove(&head_index)
@@ -124,6 +377,7 @@ impl<T> LinkedList<T> {
     }
 
     // 尾部删除节点
+    #[must_use = "pop_back returns the removed value or ListEmpty"]
     pub fn pop_back(&mut self) -> Result<T, ListError> {
         if let Some(tail_index) = self.tail {
             self.remove(&tail_index)
@@ -132,32 +386,258 @@ impl<T> LinkedList<T> {
         }
     }
 
+    // 将 index 节点移动到头部，按照 expiration_policy 决定是否重新计算淘汰时间
+    // 返回的是该节点的最新 index，原来的 index 会失效！
+    #[cfg(feature = "std")]
+    #[must_use = "the old index is invalidated; use the returned one"]
+    pub fn reposition_to_head(&mut self, index: &Index) -> Result<Index, ListError> {
+        let refresh_ttl = self.policy == ExpirationPolicy::Sliding;
+        self.reposition_to_head_with(index, refresh_ttl)
+    }
+
     // 将 index 节点移动到头部
     // 返回的是该节点的最新 index，原来的 index 会失效！
+    #[cfg(not(feature = "std"))]
+    #[must_use = "the old index is invalidated; use the returned one"]
     pub fn reposition_to_head(&mut self, index: &Index) -> Result<Index, ListError> {
-        let value = self.remove(&index)?;
+        let value = self.remove(index)?;
         self.push_front(value)
     }
 
+    // 将 index 节点移动到头部，按照 expiration_policy 决定是否重新计算淘汰时间
+    // 与 reposition_to_head 不同，只修改链接而不重新分配内存单位，节点的 index 保持不变
+    #[cfg(feature = "std")]
+    #[must_use = "moving a stale index fails with LinkBroken"]
+    pub fn move_to_head(&mut self, index: &Index) -> Result<(), ListError> {
+        let refresh_ttl = self.policy == ExpirationPolicy::Sliding;
+        self.move_to_head_with(index, refresh_ttl)
+    }
+
+    // 将 index 节点移动到头部，节点的 index 保持不变
+    #[cfg(not(feature = "std"))]
+    #[must_use = "moving a stale index fails with LinkBroken"]
+    pub fn move_to_head(&mut self, index: &Index) -> Result<(), ListError> {
+        self.relink_to_head(index)
+    }
+
+    // 将 index 节点移动到头部并更新最近访问时间，节点的 index 保持不变
+    // refresh_ttl 为 true 时按照节点自身的存活时间重新计算淘汰时间，
+    // 单独设置了绝对淘汰时间的节点保留原有的淘汰时间；refresh_ttl 为 false 时总是保留
+    // 保留的淘汰时间可能早于其它节点，此时之后的 retire 会遍历整个链表
+    #[cfg(feature = "std")]
+    #[must_use = "moving a stale index fails with LinkBroken"]
+    pub fn move_to_head_with(&mut self, index: &Index, refresh_ttl: bool) -> Result<(), ListError> {
+        self.relink_to_head(index)?;
+        let now = self.now();
+        let ttl = self.get(index)?.ttl;
+        let (expire_time, stale_time) = self.deadlines_for(now, ttl);
+        let node = self.get_mut(index)?;
+        node.last_access = now;
+        if refresh_ttl && ttl.is_some() {
+            node.expire_time = expire_time;
+            node.stale_time = stale_time;
+        }
+        self.check_order(index)
+    }
+
+    // 将 index 节点从当前位置摘下，再链接到头部
+    fn relink_to_head(&mut self, index: &Index) -> Result<(), ListError> {
+        let prev_index = match self.get(index)?.prev {
+            Some(prev_index) => prev_index,
+            // 已经是头节点
+            None => return Ok(()),
+        };
+        let next = self.get(index)?.next;
+        let old_head_index = match self.head {
+            Some(head_index) => head_index,
+            None => {
+                // 头指针丢失，沿着 prev 找到实际的头节点并报告它的位置
+                let mut head_index = prev_index;
+                for _ in 0..self.len {
+                    match self.get(&head_index)?.prev {
+                        Some(prev_index) => head_index = prev_index,
+                        None => break,
+                    }
+                }
+                return Err(ListError::link_broken(&head_index));
+            }
+        };
+
+        // 前一个节点跳过 index 节点，index 节点是尾节点时前一个节点成为新的尾节点
+        self.get_mut(&prev_index)?.next = next;
+        match next {
+            Some(next_index) => self.get_mut(&next_index)?.prev = Some(prev_index),
+            None => self.tail = Some(prev_index),
+        }
+
+        self.get_mut(&old_head_index)?.prev = Some(*index);
+        let node = self.get_mut(index)?;
+        node.prev = None;
+        node.next = Some(old_head_index);
+        self.head = Some(*index);
+        Ok(())
+    }
+
+    // 将 index 节点移动到头部，淘汰时间的处理与 move_to_head_with 相同
+    // 返回的是该节点的最新 index，原来的 index 会失效！
+    #[cfg(feature = "std")]
+    #[must_use = "the old index is invalidated; use the returned one"]
+    pub fn reposition_to_head_with(
+        &mut self,
+        index: &Index,
+        refresh_ttl: bool,
+    ) -> Result<Index, ListError> {
+        let node = self.get(index)?;
+        let (expire_time, stale_time, ttl) = (node.expire_time, node.stale_time, node.ttl);
+        let value = self.remove(index)?;
+        let index = self.push_front(value)?;
+        let node = self.get_mut(&index)?;
+        node.expire_time = expire_time;
+        node.stale_time = stale_time;
+        node.ttl = ttl;
+        if refresh_ttl {
+            self.refresh_expire_time(&index)?;
+        }
+        self.check_order(&index)?;
+        Ok(index)
+    }
+
+    // 按照节点自身的存活时间重新计算 index 节点的淘汰时间，不移动节点
+    // 节点没有存活时间（没有淘汰时间或者单独设置了绝对淘汰时间）时不做任何修改
+    #[cfg(feature = "std")]
+    pub fn refresh_expire_time(&mut self, index: &Index) -> Result<(), ListError> {
+        match self.get(index)?.ttl {
+            Some(ttl) => self.set_ttl(index, Some(ttl)),
+            None => Ok(()),
+        }
+    }
+
+    // 将 index 节点的存活时间设置为 ttl，并从当前时间开始重新计算淘汰时间
+    // ttl 为 None 时清除淘汰时间，使其不会被超时淘汰
+    #[cfg(feature = "std")]
+    pub fn set_ttl(&mut self, index: &Index, ttl: Option<time::Duration>) -> Result<(), ListError> {
+        let (expire_time, stale_time) = self.deadlines_for(self.now(), ttl);
+        let node = self.get_mut(index)?;
+        node.ttl = ttl;
+        node.expire_time = expire_time;
+        node.stale_time = stale_time;
+        self.check_order(index)
+    }
+
+    // 按照从头到尾的顺序将节点重新放入一块新的连续内存，容量不变
+    // 整理后节点在内存中按链表顺序排列，空闲区域也变为连续的
+    // 所有旧的 index 都会失效！按从头到尾的顺序返回节点新的 index
+    pub fn compact(&mut self) -> Result<Vec<Index>, ListError> {
+        let mut arena =
+            Arena::new_with_cap(self.arena.cap()).with_generation(self.arena.max_generation());
+        let mut indices = Vec::with_capacity(self.len);
+        let mut current = self.head;
+        while let Some(index) = current {
+            let mut node = self
+                .arena
+                .remove(&index)
+                .ok_or_else(|| ListError::link_broken(&index))?;
+            current = node.next;
+            node.prev = indices.last().copied();
+            node.next = None;
+
+            let new_index = arena.insert(node)?;
+            if let Some(prev_index) = indices.last() {
+                if let Some(prev) = arena.get_mut(prev_index) {
+                    prev.next = Some(new_index);
+                }
+            }
+            indices.push(new_index);
+        }
+
+        self.arena = arena;
+        self.head = indices.first().copied();
+        self.tail = indices.last().copied();
+        Ok(indices)
+    }
+
+    // 删除所有节点，容量和超时设置不变
+    // 底层 Arena 会被重建，空闲区域重新变为连续的，之前的 index 全部失效
+    pub fn clear(&mut self) {
+        self.arena =
+            Arena::new_with_cap(self.arena.cap()).with_generation(self.arena.max_generation());
+        self.head = None;
+        self.tail = None;
+        self.len = 0;
+        #[cfg(feature = "std")]
+        {
+            self.unordered = false;
+        }
+    }
+
+    // 释放底层 Arena 中所有空闲的内存单位，容量缩小为节点个数
+    // 被移动的节点的 index 会失效！返回所有节点的 (旧 index, 新 index)
+    pub fn reclaim(&mut self) -> Result<Vec<(Index, Index)>, ListError> {
+        let remap = self.arena.compact();
+        self.apply_remap(remap)
+    }
+
+    // 与 reclaim 相同，并将释放的内存归还给分配器
+    pub fn shrink_to_fit(&mut self) -> Result<Vec<(Index, Index)>, ListError> {
+        let remap = self.arena.shrink_to_fit();
+        self.apply_remap(remap)
+    }
+
+    // 根据 Arena 整理后的 (旧 index, 新 index) 更新头尾节点和所有节点的链接
+    fn apply_remap(
+        &mut self,
+        remap: Vec<(Index, Index)>,
+    ) -> Result<Vec<(Index, Index)>, ListError> {
+        let lookup: HashMap<Index, Index> = remap.iter().copied().collect();
+        let fix = |index: Option<Index>| -> Result<Option<Index>, ListError> {
+            index
+                .map(|index| {
+                    lookup
+                        .get(&index)
+                        .copied()
+                        .ok_or_else(|| ListError::link_broken(&index))
+                })
+                .transpose()
+        };
+
+        self.head = fix(self.head)?;
+        self.tail = fix(self.tail)?;
+        for (_, index) in &remap {
+            let node = self.get_mut(index)?;
+            node.prev = fix(node.prev)?;
+            node.next = fix(node.next)?;
+        }
+        Ok(remap)
+    }
+
+    // 底层 Arena 空闲链表的碎片程度
+    pub fn fragmentation(&self) -> f32 {
+        self.arena.fragmentation()
+    }
+
     // 返回头节点的值
     pub fn peek_front(&self) -> Result<&T, ListError> {
         let head_index = self.head.ok_or(ListError::ListEmpty)?;
-        return self.get(&head_index).map(|x| &x.value);
+        self.get(&head_index).map(|x| &x.value)
     }
 
     // 返回尾节点的值
     pub fn peek_back(&self) -> Result<&T, ListError> {
         let tail_index = self.tail.ok_or(ListError::ListEmpty)?;
-        return self.get(&tail_index).map(|x| &x.value);
+        self.get(&tail_index).map(|x| &x.value)
     }
 
     // 根据节点索引删除该节点，返回该节点值的所有权
+    #[must_use = "remove returns the removed value or LinkBroken"]
     pub fn remove(&mut self, index: &Index) -> Result<T, ListError> {
         if self.is_empty() {
             return Err(ListError::ListEmpty);
         }
 
-        let node = self.arena.remove(index).ok_or(ListError::LinkBroken)?;
+        let node = self
+            .arena
+            .remove(index)
+            .ok_or_else(|| ListError::link_broken(index))?;
 
         match (node.prev, node.next) {
             (Some(prev_index), Some(next_index)) => {
@@ -188,25 +668,139 @@ impl<T> LinkedList<T> {
     }
 
     // 从链表尾开始淘汰过期节点，并返回其值的所有权的集合
+    // 没有淘汰时间的节点视为永不过期
+    #[cfg(feature = "std")]
     pub fn retire(&mut self) -> Result<Option<Vec<T>>, ListError> {
-        if let Some(_) = self.timeout {
-            let now = time::Instant::now();
-            let mut values = vec![];
-            while !self.is_empty() {
-                let tail_index = self.tail.unwrap();
-                let expire_time = self.get(&tail_index)?.expire_time.unwrap();
-                if now >= expire_time {
-                    values.push(self.remove(&tail_index)?);
-                } else {
-                    break;
+        self.retire_at(self.now())
+    }
+
+    // 以 now 作为当前时间，从链表尾开始淘汰过期节点
+    // 设置了宽限时间时，节点在宽限期结束后才会被淘汰
+    // 遇到第一个未过期的节点时停止
+    // 设置了 max_idle，或者单独设置淘汰时间、Fixed 策略下移动节点等操作使顺序被打乱时，
+    // 过期的节点不一定位于尾部，此时与 retire_all 相同会遍历整个链表
+    #[cfg(feature = "std")]
+    pub fn retire_at(&mut self, now: time::Instant) -> Result<Option<Vec<T>>, ListError> {
+        if self.max_idle.is_some() || self.unordered {
+            return self.retire_all_at(now);
+        }
+        let mut values = vec![];
+        while let Some(tail_index) = self.tail {
+            if !self.is_retirable(self.get(&tail_index)?, now) {
+                break;
+            }
+            values.push(self.remove(&tail_index)?);
+        }
+        if !values.is_empty() {
+            return Ok(Some(values));
+        }
+        // 如果没有一个被淘汰，返回 None，而不是 vec![]
+        Ok(None)
+    }
+
+    // 遍历整个链表，淘汰所有过期的节点，并按照从尾到头的顺序返回其值的所有权的集合
+    // 节点的淘汰时间不一致时（例如设置了单独的淘汰时间），可以淘汰 retire 遗漏的中间节点
+    #[cfg(feature = "std")]
+    pub fn retire_all(&mut self) -> Result<Option<Vec<T>>, ListError> {
+        self.retire_all_at(self.now())
+    }
+
+    // 遍历的同时检查剩余节点的宽限截止时间是否从尾到头递增（没有截止时间视为无穷大）
+    // 顺序恢复后 retire 重新只从尾部开始检查
+    #[cfg(feature = "std")]
+    fn retire_all_at(&mut self, now: time::Instant) -> Result<Option<Vec<T>>, ListError> {
+        let mut expired = vec![];
+        let mut ordered = true;
+        let mut latest: Option<time::Instant> = None;
+        let mut never_expires = false;
+        let mut current = self.tail;
+        while let Some(index) = current {
+            let node = self.get(&index)?;
+            if self.is_retirable(node, now) {
+                expired.push(index);
+            } else {
+                match node.stale_time {
+                    Some(stale_time) => {
+                        if never_expires || latest.is_some_and(|latest| stale_time < latest) {
+                            ordered = false;
+                        }
+                        latest = Some(stale_time);
+                    }
+                    None => never_expires = true,
                 }
             }
-            if values.len() > 0 {
-                return Ok(Some(values));
+            current = node.prev;
+        }
+        self.unordered = !ordered;
+        if expired.is_empty() {
+            return Ok(None);
+        }
+        let values = expired
+            .iter()
+            .map(|index| self.remove(index))
+            .collect::<Result<Vec<T>, ListError>>()?;
+        Ok(Some(values))
+    }
+
+    // 以 now 作为当前时间，统计 retire_at 会淘汰的节点个数，不做任何修改
+    #[cfg(feature = "std")]
+    pub fn count_retirable_at(&self, now: time::Instant) -> usize {
+        let mut count = 0;
+        let mut current = self.tail;
+        while let Some(index) = current {
+            let node = match self.get(&index) {
+                Ok(node) => node,
+                Err(_) => break,
+            };
+            if self.is_retirable(node, now) {
+                count += 1;
+            } else if self.max_idle.is_none() && !self.unordered {
+                // 与 retire_at 相同，只从尾部检查时遇到第一个未过期的节点就停止
+                break;
             }
-            // 如果没有一个被淘汰，返回 None，而不是 vec![]
+            current = node.prev;
         }
-        Ok(None)
+        count
+    }
+
+    // 将 index 节点的淘汰时间设置为绝对时间 expire_time
+    // 之后的 retire 会遍历整个链表，直到淘汰时间重新变为从尾到头递增
+    // 节点被移动到头部后仍然保留该淘汰时间，直到再次设置存活时间
+    #[cfg(feature = "std")]
+    pub fn set_expire_time(
+        &mut self,
+        index: &Index,
+        expire_time: time::Instant,
+    ) -> Result<(), ListError> {
+        let stale_time = self.stale_time_for(expire_time);
+        let node = self.get_mut(index)?;
+        node.expire_time = Some(expire_time);
+        node.stale_time = stale_time;
+        node.ttl = None;
+        self.unordered = true;
+        Ok(())
+    }
+
+    // 清除 index 节点的淘汰时间，使其不会被超时淘汰
+    #[cfg(feature = "std")]
+    pub fn clear_expire_time(&mut self, index: &Index) -> Result<(), ListError> {
+        let node = self.get_mut(index)?;
+        node.expire_time = None;
+        node.stale_time = None;
+        node.ttl = None;
+        self.unordered = true;
+        Ok(())
+    }
+
+    // 设置 index 节点的最近访问时间
+    #[cfg(feature = "std")]
+    pub fn set_last_access(
+        &mut self,
+        index: &Index,
+        last_access: time::Instant,
+    ) -> Result<(), ListError> {
+        self.get_mut(index)?.last_access = last_access;
+        Ok(())
     }
 
     pub fn len(&self) -> usize {
@@ -217,58 +811,257 @@ impl<T> LinkedList<T> {
         self.head.is_none()
     }
 
+    // 从头节点开始统计 last_access 不早于 since 的节点个数
+    // 遇到第一个更早的节点时停止，因此要求节点按照访问时间从新到旧排列
+    #[cfg(feature = "std")]
+    pub fn count_accessed_since(&self, since: time::Instant) -> usize {
+        let mut count = 0;
+        let mut current = self.head;
+        while let Some(index) = current {
+            match self.get(&index) {
+                Ok(node) if node.last_access >= since => {
+                    count += 1;
+                    current = node.next;
+                }
+                _ => break,
+            }
+        }
+        count
+    }
+
+    // 链表最多可以容纳的节点个数
+    pub fn capacity(&self) -> usize {
+        self.arena.cap()
+    }
+
+    // 底层 Arena 的代数
+    pub fn max_generation(&self) -> u64 {
+        self.arena.max_generation()
+    }
+
     pub fn is_full(&self) -> bool {
         self.len == self.arena.cap()
     }
 
     pub fn get(&self, index: &Index) -> Result<&Node<T>, ListError> {
-        self.arena.get(index).ok_or(ListError::LinkBroken)
+        self.arena
+            .get(index)
+            .ok_or_else(|| ListError::link_broken(index))
     }
 
     pub fn get_mut(&mut self, index: &Index) -> Result<&mut Node<T>, ListError> {
-        self.arena.get_mut(index).ok_or(ListError::LinkBroken)
+        self.arena
+            .get_mut(index)
+            .ok_or_else(|| ListError::link_broken(index))
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::thread;
+    // 从头节点到尾节点遍历链表中的值
+    // 同时支持 next_back 从尾节点开始遍历，两端相遇后停止
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            front: self.head,
+            back: self.tail,
+        }
+    }
 
-    use super::*;
+    // 从尾节点到头节点遍历链表中的值，即 iter 的逆序
+    pub fn iter_rev(&self) -> IterRev<'_, T> {
+        IterRev {
+            list: self,
+            current: self.tail,
+        }
+    }
 
-    impl<T> LinkedList<T> {
-        fn iter(&self) -> Iter<T> {
-            Iter {
-                list: self,
-                current: self.head,
+    // 从头节点到尾节点依次获取链表中值的可变引用
+    // 与 iter 相同，遇到损坏的链接时停止
+    // 先沿着 next 校验每个节点的 prev 都指向上一个节点（头节点的 prev 为 None），
+    // 校验通过的节点互不相同，之后只返回这些节点，因此返回的可变引用不会重叠
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let mut remaining = 0;
+        let mut prev = None;
+        let mut current = self.head;
+        while let Some(index) = current {
+            match self.get(&index) {
+                Ok(node) if node.prev == prev => {
+                    prev = current;
+                    current = node.next;
+                    remaining += 1;
+                }
+                _ => break,
             }
         }
+        IterMut {
+            current: self.head,
+            remaining,
+            arena: self.arena.raw_mut(),
+        }
     }
+}
+
+pub struct Iter<'a, T: 'a> {
+    list: &'a LinkedList<T>,
+    // 下一个从头部和尾部返回的节点，两端相遇后都为 None
+    front: Option<Index>,
+    back: Option<Index>,
+}
 
-    struct Iter<'a, T: 'a> {
-        list: &'a LinkedList<T>,
-        current: Option<Index>,
+impl<'a, T: 'a> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.front?;
+        let node = self.list.get(&index).ok()?;
+        if self.front == self.back {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.front = node.next;
+        }
+        Some(&node.value)
     }
-    impl<'a, T: 'a> Iterator for Iter<'a, T> {
-        type Item = &'a T;
+}
 
-        fn next(&mut self) -> Option<Self::Item> {
-            if let Some(index) = self.current {
-                if let Ok(node) = self.list.get(&index) {
-                    self.current = node.next;
-                    return Some(&node.value);
-                }
+impl<'a, T: 'a> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.back?;
+        let node = self.list.get(&index).ok()?;
+        if self.front == self.back {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.back = node.prev;
+        }
+        Some(&node.value)
+    }
+}
+
+pub struct IterRev<'a, T: 'a> {
+    list: &'a LinkedList<T>,
+    current: Option<Index>,
+}
+
+impl<'a, T: 'a> Iterator for IterRev<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(index) = self.current {
+            if let Ok(node) = self.list.get(&index) {
+                self.current = node.prev;
+                return Some(&node.value);
             }
+        }
+
+        None
+    }
+}
+
+pub struct IterMut<'a, T: 'a> {
+    arena: RawArena<'a, Node<T>>,
+    current: Option<Index>,
+    // 创建时校验过的、还没有返回的节点个数
+    remaining: usize,
+}
+
+impl<'a, T: 'a> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
 
-            None
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
         }
+        let index = self.current?;
+        self.remaining -= 1;
+        // SAFETY: 这些节点在 iter_mut 中校验过互不相同，每个节点只会被获取一次
+        let node = unsafe { self.arena.get_mut(&index) }?;
+        self.current = node.next;
+        Some(&mut node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+// 与 &mut T 相同，裸指针只是用来同时持有多个节点的可变引用
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+#[cfg(all(test, feature = "std"))]
+impl<T> LinkedList<T> {
+    pub(crate) fn arena_mut(&mut self) -> &mut Arena<Node<T>> {
+        &mut self.arena
     }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::lru::clock::MockClock;
+
+    use super::*;
 
     #[test]
     fn list_new() {
-        let list = LinkedList::<i32>::new();
+        let mut list = LinkedList::<i32>::new();
         assert!(list.is_empty());
         assert!(list.is_full());
+        assert_eq!(list.capacity(), 0);
+
+        // 容量为 0 时需要先扩容
+        assert_eq!(list.push_front(0), Err(ListError::ZeroCapacity));
+        assert_eq!(list.push_back(0), Err(ListError::ZeroCapacity));
+        assert_eq!(
+            ListError::ZeroCapacity.to_string(),
+            "List has zero capacity; reserve capacity before inserting."
+        );
+        list.reserve(1);
+        assert!(list.push_back(0).is_ok());
+    }
+
+    #[test]
+    fn list_debug() {
+        let mut list = LinkedList::<i32>::new_with_cap(3);
+        assert_eq!(format!("{list:?}"), "[]");
+        list.push_front(1).unwrap();
+        list.push_front(2).unwrap();
+        list.push_back(0).unwrap();
+        assert_eq!(format!("{list:?}"), "[2, 1, 0]");
+    }
+
+    #[test]
+    fn list_reclaim() {
+        let mut list = LinkedList::<i32>::new_with_cap(6);
+        let indices: Vec<Index> = (0..6).map(|ele| list.push_front(ele).unwrap()).collect();
+        // [5 3 2 0]
+        list.remove(&indices[1]).unwrap();
+        list.remove(&indices[4]).unwrap();
+
+        let remap = list.reclaim().unwrap();
+        assert_eq!(remap.len(), 4);
+        assert_eq!(list.capacity(), 4);
+        assert!(list.is_full());
+        assert!(list.iter().eq(&[5, 3, 2, 0]));
+        for (_, index) in &remap {
+            assert!(list.get(index).is_ok());
+        }
+
+        // 链接关系保持正确
+        assert_eq!(list.pop_back(), Ok(0));
+        assert_eq!(list.pop_front(), Ok(5));
+        assert!(list.iter().eq(&[3, 2]));
+        list.reserve(1);
+        list.push_front(6).unwrap();
+        assert!(list.iter().eq(&[6, 3, 2]));
+    }
+
+    #[test]
+    fn list_capacity() {
+        let mut list = LinkedList::<i32>::new_with_cap(3);
+        assert_eq!(list.capacity(), 3);
+        list.push_front(0).unwrap();
+        assert_eq!(list.capacity(), 3);
+        list.reserve(2);
+        assert_eq!(list.capacity(), 5);
     }
 
     #[test]
@@ -286,6 +1079,92 @@ mod tests {
         }
     }
 
+    #[test]
+    fn list_iter_rev() {
+        let mut list = LinkedList::<i32>::new_with_cap(5);
+        assert_eq!(list.iter_rev().next(), None);
+
+        list.push_front(0).unwrap();
+        assert!(list.iter_rev().eq(&[0]));
+
+        // [1, 0, 4, 3, 2]
+        for ele in 1..5 {
+            list.push_front(ele).unwrap();
+        }
+        list.reposition_to_head(&list.tail.unwrap()).unwrap();
+        list.reposition_to_head(&list.tail.unwrap()).unwrap();
+        let forward: Vec<_> = list.iter().collect();
+        let mut backward: Vec<_> = list.iter_rev().collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+        assert!(list.iter_rev().eq(&[2, 3, 4, 0, 1]));
+    }
+
+    #[test]
+    fn list_iter_double_ended() {
+        let mut list = LinkedList::<i32>::new_with_cap(5);
+        let mut iter = list.iter();
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+
+        // 只有一个节点时头尾相同，只会返回一次
+        list.push_front(0).unwrap();
+        let mut iter = list.iter();
+        assert_eq!(iter.next_back(), Some(&0));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        // [4, 3, 2, 1, 0]
+        for ele in 1..5 {
+            list.push_front(ele).unwrap();
+        }
+        assert!(list.iter().rev().eq(list.iter_rev()));
+
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&4));
+        assert_eq!(iter.next_back(), Some(&0));
+        assert_eq!(iter.next_back(), Some(&1));
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+
+        // 节点个数为偶数时两端在相邻节点之间相遇
+        list.pop_back().unwrap();
+        let mut iter = list.iter();
+        assert_eq!(iter.next_back(), Some(&1));
+        assert_eq!(iter.next(), Some(&4));
+        assert_eq!(iter.next_back(), Some(&2));
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+
+    #[test]
+    fn list_iter_mut() {
+        let mut list = LinkedList::<i32>::new_with_cap(5);
+        assert_eq!(list.iter_mut().count(), 0);
+
+        // [4, 3, 2, 1, 0]，调整顺序后链表顺序与 Arena 中的存储顺序不同
+        for ele in 0..5 {
+            list.push_front(ele).unwrap();
+        }
+        list.reposition_to_head(&list.tail.unwrap()).unwrap();
+        assert!(list.iter().eq(&[0, 4, 3, 2, 1]));
+
+        for ele in list.iter_mut() {
+            *ele += 10;
+        }
+        assert!(list.iter().eq(&[10, 14, 13, 12, 11]));
+        assert_eq!(list.len(), 5);
+
+        // 尾节点的 next 被破坏成指向头节点时，不会重复返回同一个节点
+        let (head, tail) = (list.head.unwrap(), list.tail.unwrap());
+        list.get_mut(&tail).unwrap().next = Some(head);
+        assert_eq!(list.iter_mut().size_hint(), (5, Some(5)));
+        assert_eq!(list.iter_mut().count(), 5);
+    }
+
     #[test]
     fn list_reposition_to_head() {
         let capacity = 5;
@@ -304,10 +1183,9 @@ mod tests {
         }
         assert!(list.iter().eq([2, 3, 4, 0, 1].iter()));
 
-        let mut i = 0;
-        let mut rh = 0 as i32;
+        let mut rh = 0;
         let mut lh = capacity as i32 / 2;
-        for ele in list.iter() {
+        for (i, ele) in list.iter().enumerate() {
             if i <= (capacity / 2) {
                 assert_eq!(ele, &lh);
                 lh += 1;
@@ -315,7 +1193,6 @@ mod tests {
                 assert_eq!(ele, &rh);
                 rh += 1;
             }
-            i += 1
         }
 
         let mut list = LinkedList::<i32>::new_with_cap(2);
@@ -410,17 +1287,19 @@ mod tests {
     #[test]
     fn list_retire() {
         let capacity = 10;
+        let clock = MockClock::new();
         let mut list =
             LinkedList::<i32>::new_with_cap_timeout(capacity, time::Duration::from_millis(1000));
+        list.set_clock(Arc::new(clock.clone()));
         for ele in 0..5 {
-            assert!(list.push_front(ele as i32).is_ok());
+            assert!(list.push_front(ele).is_ok());
         }
 
-        thread::sleep(time::Duration::from_millis(500));
+        clock.advance(time::Duration::from_millis(500));
         assert_eq!(list.len(), 5);
 
         for ele in 0..5 {
-            assert!(list.push_front(5 + ele as i32).is_ok());
+            assert!(list.push_front(5 + ele).is_ok());
         }
         assert_eq!(list.len(), 10);
 
@@ -428,16 +1307,105 @@ mod tests {
         assert!(list.retire().unwrap().is_none());
         assert_eq!(list.len(), 10);
 
-        thread::sleep(time::Duration::from_millis(500));
+        clock.advance(time::Duration::from_millis(500));
         assert_eq!(list.retire().unwrap().unwrap(), vec![0, 1, 2, 3, 4]);
         assert_eq!(list.len(), 5);
         assert_eq!(list.pop_back().unwrap(), 5);
 
-        thread::sleep(time::Duration::from_millis(500));
+        clock.advance(time::Duration::from_millis(500));
         assert_eq!(list.retire().unwrap().unwrap(), vec![6, 7, 8, 9]);
         assert_eq!(list.len(), 0);
 
         assert!(list.retire().unwrap().is_none());
+    }
+
+    #[test]
+    fn list_retire_max_idle() {
+        let clock = MockClock::new();
+        let start = clock.now();
+        let mut list = LinkedList::<i32>::new_with_cap(5);
+        list.set_clock(Arc::new(clock.clone()));
+        list.set_max_idle(time::Duration::from_secs(10));
+        clock.advance(time::Duration::from_secs(5));
+        // [2 1 0]
+        list.push_front(0).unwrap();
+        let link_1 = list.push_front(1).unwrap();
+        list.push_front(2).unwrap();
+        // 中间节点的最近访问时间早于尾部节点
+        list.set_last_access(&link_1, start).unwrap();
+
+        clock.advance(time::Duration::from_secs(6));
+        assert_eq!(list.count_retirable_at(list.now()), 1);
+        assert_eq!(list.retire().unwrap(), Some(vec![1]));
+        assert!(list.iter().eq(&[2, 0]));
+
+        clock.advance(time::Duration::from_secs(5));
+        assert_eq!(list.retire().unwrap(), Some(vec![0, 2]));
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn list_retire_unordered() {
+        let clock = MockClock::new();
+        let now = clock.now();
+        let mut list = LinkedList::<i32>::new_with_cap(5);
+        list.set_clock(Arc::new(clock.clone()));
+        // [3 2 1 0]
+        let link_0 = list.push_front(0).unwrap();
+        list.push_front(1).unwrap();
+        let link_2 = list.push_front(2).unwrap();
+        let link_3 = list.push_front(3).unwrap();
+        // 头部节点的淘汰时间早于尾部节点
+        list.set_expire_time(&link_0, now + time::Duration::from_secs(3))
+            .unwrap();
+        list.set_expire_time(&link_2, now + time::Duration::from_secs(2))
+            .unwrap();
+        list.set_expire_time(&link_3, now + time::Duration::from_secs(1))
+            .unwrap();
+
+        clock.advance(time::Duration::from_secs(1));
+        assert_eq!(list.count_retirable_at(list.now()), 1);
+        assert_eq!(list.retire().unwrap(), Some(vec![3]));
+        assert!(list.iter().eq(&[2, 1, 0]));
+
+        clock.advance(time::Duration::from_secs(1));
+        assert_eq!(list.retire().unwrap(), Some(vec![2]));
+        assert!(list.iter().eq(&[1, 0]));
+
+        // 没有淘汰时间的节点位于尾部之前，顺序仍然被打乱
+        clock.advance(time::Duration::from_secs(1));
+        assert_eq!(list.retire().unwrap(), Some(vec![0]));
+        assert!(list.iter().eq(&[1]));
+        assert_eq!(list.retire().unwrap(), None);
+    }
+
+    #[test]
+    fn list_retire_all() {
+        let clock = MockClock::new();
+        let mut list =
+            LinkedList::<i32>::new_with_cap_timeout(5, time::Duration::from_millis(100));
+        list.set_clock(Arc::new(clock.clone()));
+        list.set_expiration_policy(ExpirationPolicy::Fixed);
+        let link_0 = list.push_front(0).unwrap();
+        let link_1 = list.push_front(1).unwrap();
+        clock.advance(time::Duration::from_millis(50));
+        list.push_front(2).unwrap();
+        list.push_front(3).unwrap();
+        // Fixed 策略下移动到头部不会延长淘汰时间，头部节点的淘汰时间早于尾部节点
+        // [0 1 3 2]
+        list.move_to_head(&link_1).unwrap();
+        list.move_to_head(&link_0).unwrap();
+        clock.advance(time::Duration::from_millis(50));
+
+        // 尾部节点没有过期，但头部的过期节点同样会被统计和淘汰
+        assert_eq!(list.count_retirable_at(list.now()), 2);
+        assert_eq!(list.retire_all().unwrap(), Some(vec![1, 0]));
+        assert_eq!(list.len(), 2);
+        assert!(list.iter().eq(&[3, 2]));
+        assert_eq!(list.peek_back(), Ok(&2));
+        assert_eq!(list.pop_back(), Ok(2));
+        assert_eq!(list.pop_back(), Ok(3));
+        assert!(list.retire_all().unwrap().is_none());
     } 
 
 
@@ -446,11 +1414,121 @@ mod tests {
             &self.value
         }
     }
+    #[test]
+    fn list_move_to_head() {
+        let mut list = LinkedList::<i32>::new_with_cap(4);
+        // [3 2 1 0]
+        let indices: Vec<Index> = (0..4).map(|ele| list.push_front(ele).unwrap()).collect();
+        let generation = list.max_generation();
+
+        // 中间节点 [1 3 2 0]
+        list.move_to_head(&indices[1]).unwrap();
+        assert!(list.iter().eq(&[1, 3, 2, 0]));
+        // 尾节点 [0 1 3 2]
+        list.move_to_head(&indices[0]).unwrap();
+        assert!(list.iter().eq(&[0, 1, 3, 2]));
+        assert!(list.iter().rev().eq(&[2, 3, 1, 0]));
+        // 头节点不变
+        list.move_to_head(&indices[0]).unwrap();
+        assert!(list.iter().eq(&[0, 1, 3, 2]));
+
+        // index 保持不变，没有重新分配内存单位
+        for (ele, index) in indices.iter().enumerate() {
+            assert_eq!(list.get(index).unwrap().value, ele as i32);
+        }
+        assert_eq!(list.max_generation(), generation);
+        assert_eq!(list.peek_back(), Ok(&2));
+        assert_eq!(list.pop_back(), Ok(2));
+        assert_eq!(list.pop_front(), Ok(0));
+        assert!(list.iter().eq(&[1, 3]));
+
+        let mut list = LinkedList::<i32>::new_with_cap(1);
+        let index = list.push_front(0).unwrap();
+        list.move_to_head(&index).unwrap();
+        assert!(list.iter().eq(&[0]));
+        list.pop_front().unwrap();
+        assert_eq!(
+            list.move_to_head(&index),
+            Err(ListError::link_broken(&index))
+        );
+    }
+
+    #[test]
+    fn list_link_broken() {
+        let mut list = LinkedList::<i32>::new_with_cap(2);
+        let stale = list.push_front(0).unwrap();
+        list.remove(&stale).unwrap();
+        // 内存单位被复用后，旧 index 的代数与其不匹配
+        list.push_front(1).unwrap();
+
+        let error = ListError::LinkBroken {
+            idx: 0,
+            generation: 0,
+        };
+        assert_eq!(list.get(&stale).err(), Some(error.clone()));
+        assert_eq!(list.get_mut(&stale).err(), Some(error.clone()));
+        assert_eq!(list.remove(&stale), Err(error.clone()));
+        assert_eq!(
+            error.to_string(),
+            "Link does not point to a valid location (index 0, generation 0)."
+        );
+    }
+
+    #[test]
+    fn list_relink_missing_head() {
+        let mut list = LinkedList::<i32>::new_with_cap(3);
+        let index_0 = list.push_front(0).unwrap();
+        list.push_front(1).unwrap();
+        let index_2 = list.push_front(2).unwrap();
+        // [2 1 0]，头指针丢失时报告实际头节点的位置，链表保持不变
+        list.head = None;
+        assert_eq!(
+            list.relink_to_head(&index_0),
+            Err(ListError::link_broken(&index_2))
+        );
+        list.head = Some(index_2);
+        assert!(list.iter().eq(&[2, 1, 0]));
+        assert_eq!(list.peek_back(), Ok(&0));
+    }
+
+    #[test]
+    fn list_move_to_head_refresh_ttl() {
+        let clock = MockClock::new();
+        let mut list = LinkedList::<i32>::new_with_cap_timeout(3, time::Duration::from_secs(10));
+        list.set_clock(Arc::new(clock.clone()));
+        let index_0 = list.push_front(0).unwrap();
+        let index_1 = list.push_front(1).unwrap();
+        let expire_time = list.get(&index_0).unwrap().expire_time();
+
+        clock.advance(time::Duration::from_secs(5));
+        list.move_to_head(&index_0).unwrap();
+        let node = list.get(&index_0).unwrap();
+        assert_eq!(
+            node.expire_time(),
+            Some(list.now() + time::Duration::from_secs(10))
+        );
+        assert_eq!(node.last_access(), list.now());
+
+        // 保留原有的淘汰时间
+        list.move_to_head_with(&index_1, false).unwrap();
+        assert_eq!(list.get(&index_1).unwrap().expire_time(), expire_time);
+        assert_eq!(list.get(&index_1).unwrap().last_access(), list.now());
+        assert!(list.iter().eq(&[1, 0]));
+
+        // 头部节点先于尾部节点过期，retire 不会因为尾部节点没有过期而遗漏它
+        clock.advance(time::Duration::from_secs(5));
+        assert_eq!(list.count_retirable_at(list.now()), 1);
+        assert_eq!(list.retire().unwrap(), Some(vec![1]));
+        assert!(list.iter().eq(&[0]));
+    }
+
     #[test]
     fn list_retire_and_reposition_to_head() {
         let capacity = 5;
+        let clock = MockClock::new();
         let mut list =
             LinkedList::<i32>::new_with_cap_timeout(capacity, time::Duration::from_millis(1000));
+        list.set_clock(Arc::new(clock.clone()));
 
         let mut live_index = list.head;
         for ele in 0..capacity {
@@ -463,7 +1541,7 @@ mod tests {
         assert_eq!(list.len(), capacity);
 
         // 此时应该节点全都过期了
-        thread::sleep(time::Duration::from_millis(1000));
+        clock.advance(time::Duration::from_millis(1000));
 
         // 更新中心节点
         let live_index = list.reposition_to_head(&live_index.unwrap()).unwrap();
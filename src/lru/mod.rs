@@ -1,4 +1,20 @@
-mod arena;
-mod list;
+pub mod arena;
+pub mod list;
+#[cfg(feature = "std")]
+pub mod clock;
 pub mod cache;
 pub mod err;
+#[cfg(feature = "std")]
+pub mod sync;
+#[cfg(feature = "std")]
+pub mod sharded;
+pub mod lfu;
+pub mod slru;
+pub mod sketch;
+#[cfg(feature = "tokio")]
+pub mod async_cache;
+
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(not(feature = "std"))]
+use hashbrown::HashMap;
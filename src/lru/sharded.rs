@@ -0,0 +1,170 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::sync::{Mutex, MutexGuard};
+
+use crate::lru::cache::Cache;
+use crate::lru::err::CacheError;
+
+// 默认的分片个数
+const DEFAULT_SHARDS: usize = 16;
+
+// 将 key 按哈希值分散到多个独立加锁的 Cache 中，不同分片的 key 之间不会互相阻塞
+// 每个分片独立进行 LRU 淘汰，因此整体的淘汰顺序只是近似的 LRU
+pub struct ShardedCache<K, V>
+where
+    K: Eq + Hash,
+{
+    shards: Vec<Mutex<Cache<K, V>>>,
+    // 分片个数总是 2 的幂，通过 hash & mask 选择分片
+    mask: usize,
+    hasher: RandomState,
+}
+
+impl<K, V> ShardedCache<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    pub fn new_with_cap(cap: usize) -> Self {
+        Self::new_with_cap_shards(cap, DEFAULT_SHARDS)
+    }
+
+    // 分片个数会向上取整为 2 的幂（至少为 1）
+    // 每个分片的容量为 cap / 分片个数（向上取整），因此总容量可能略大于 cap
+    pub fn new_with_cap_shards(cap: usize, shards: usize) -> Self {
+        let shards = shards.max(1).next_power_of_two();
+        let shard_cap = cap.div_ceil(shards);
+        ShardedCache {
+            shards: (0..shards)
+                .map(|_| Mutex::new(Cache::new_with_cap(shard_cap)))
+                .collect(),
+            mask: shards - 1,
+            hasher: RandomState::new(),
+        }
+    }
+
+    pub fn insert(&self, key: K, value: V) -> Result<Option<V>, CacheError> {
+        self.lock(&key).insert(key, value)
+    }
+
+    // 只锁住 key 所在的分片，返回 value 的拷贝（要求 V: Clone）
+    pub fn query(&self, key: &K) -> Result<V, CacheError>
+    where
+        V: Clone,
+    {
+        self.lock(key).query(key).cloned()
+    }
+
+    pub fn remove(&self, key: &K) -> Result<V, CacheError> {
+        self.lock(key).remove(key)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.lock(key).contains_key(key)
+    }
+
+    // 依次锁住每个分片求和，统计期间其它线程的修改可能导致结果不是某一时刻的精确值
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| lock(shard).len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(|shard| lock(shard).is_empty())
+    }
+
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    fn shard_index(&self, key: &K) -> usize {
+        self.hasher.hash_one(key) as usize & self.mask
+    }
+
+    fn lock(&self, key: &K) -> MutexGuard<'_, Cache<K, V>> {
+        lock(&self.shards[self.shard_index(key)])
+    }
+}
+
+// 与 SyncCache 相同，忽略锁的中毒状态
+fn lock<K, V>(shard: &Mutex<Cache<K, V>>) -> MutexGuard<'_, Cache<K, V>>
+where
+    K: Eq + Hash,
+{
+    shard.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{mpsc, Arc};
+    use std::thread;
+    use std::time;
+
+    use super::*;
+
+    #[test]
+    fn sharded_cache_new() {
+        let sharded = ShardedCache::<i32, i32>::new_with_cap_shards(100, 6);
+        assert_eq!(sharded.shard_count(), 8);
+        assert!(sharded.shards.iter().all(|shard| lock(shard).capacity() == 13));
+        assert!(sharded.is_empty());
+
+        let sharded = ShardedCache::<i32, i32>::new_with_cap_shards(4, 0);
+        assert_eq!(sharded.shard_count(), 1);
+        assert_eq!(ShardedCache::<i32, i32>::new_with_cap(64).shard_count(), DEFAULT_SHARDS);
+    }
+
+    #[test]
+    fn sharded_cache_concurrent_access() {
+        let threads = 8;
+        let per_thread = 500;
+        let sharded = Arc::new(ShardedCache::<i32, i32>::new_with_cap(threads * per_thread * 2));
+
+        let handles: Vec<_> = (0..threads as i32)
+            .map(|t| {
+                let sharded = Arc::clone(&sharded);
+                thread::spawn(move || {
+                    let base = t * per_thread as i32;
+                    for key in base..base + per_thread as i32 {
+                        assert_eq!(sharded.insert(key, key), Ok(None));
+                        assert_eq!(sharded.query(&key), Ok(key));
+                        if key % 2 == 0 {
+                            assert_eq!(sharded.remove(&key), Ok(key));
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(sharded.len(), threads * per_thread / 2);
+        for key in 0..(threads * per_thread) as i32 {
+            assert_eq!(sharded.contains_key(&key), key % 2 != 0);
+        }
+    }
+
+    #[test]
+    fn sharded_cache_independent_shards() {
+        let sharded = Arc::new(ShardedCache::<i32, i32>::new_with_cap_shards(64, 4));
+        let held = 0;
+        let other = (1..).find(|key| sharded.shard_index(key) != sharded.shard_index(&held));
+        let other = other.unwrap();
+
+        // 一个分片的锁被长时间持有时，其它分片的 key 不会被阻塞
+        let guard = sharded.lock(&held);
+        let (tx, rx) = mpsc::channel();
+        let handle = {
+            let sharded = Arc::clone(&sharded);
+            thread::spawn(move || {
+                sharded.insert(other, other).unwrap();
+                tx.send(sharded.query(&other)).unwrap();
+                // 同一分片的 key 需要等待锁被释放
+                sharded.insert(held, held).unwrap();
+            })
+        };
+        assert_eq!(rx.recv_timeout(time::Duration::from_secs(1)), Ok(Ok(other)));
+        drop(guard);
+        handle.join().unwrap();
+        assert_eq!(sharded.query(&held), Ok(held));
+    }
+}
@@ -0,0 +1,149 @@
+use alloc::{vec, vec::Vec};
+use core::hash::{BuildHasher, Hash};
+#[cfg(not(feature = "std"))]
+use hashbrown::DefaultHashBuilder as RandomState;
+#[cfg(feature = "std")]
+use std::collections::hash_map::RandomState;
+
+// 每个计数器的上限，超过后不再增加
+const MAX_COUNT: u8 = u8::MAX;
+// 累计增加 width * SAMPLE_FACTOR 次后将所有计数减半，使估计值能够跟随访问模式的变化
+const SAMPLE_FACTOR: usize = 10;
+// 计数器总数（width * depth）的上限，即最多占用 16 MiB
+const MAX_COUNTERS: usize = 1 << 24;
+
+// Count-Min Sketch：用 depth 行、每行 width 个计数器近似统计 key 的访问频率
+// 估计值取各行计数的最小值，只会高估（哈希冲突）而不会低估
+#[derive(Debug, Clone)]
+pub struct FrequencySketch {
+    counters: Vec<u8>,
+    width: usize,
+    depth: usize,
+    hasher: RandomState,
+    // 上一次减半之后累计增加的次数
+    additions: usize,
+    sample_size: usize,
+}
+
+impl FrequencySketch {
+    // width 和 depth 至少为 1，width * depth 超过 MAX_COUNTERS（包括溢出）时缩小 width
+    // depth 本身超过 MAX_COUNTERS 时缩小为 MAX_COUNTERS，实际大小可以通过 width()、depth() 获取
+    pub fn new(width: usize, depth: usize) -> Self {
+        let depth = depth.clamp(1, MAX_COUNTERS);
+        let width = width.clamp(1, MAX_COUNTERS / depth);
+        let len = width * depth;
+        FrequencySketch {
+            counters: vec![0; len],
+            width,
+            depth,
+            hasher: RandomState::default(),
+            additions: 0,
+            sample_size: width.saturating_mul(SAMPLE_FACTOR),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    // 记录 key 的一次访问
+    pub fn increment<K: Hash>(&mut self, key: &K) {
+        for slot in self.slots(key) {
+            if self.counters[slot] < MAX_COUNT {
+                self.counters[slot] += 1;
+            }
+        }
+        self.additions += 1;
+        if self.additions >= self.sample_size {
+            self.age();
+        }
+    }
+
+    // key 的访问频率估计值
+    pub fn estimate<K: Hash>(&self, key: &K) -> u8 {
+        self.slots(key)
+            .map(|slot| self.counters[slot])
+            .min()
+            .unwrap_or(0)
+    }
+
+    // 将所有计数减半
+    pub fn age(&mut self) {
+        for counter in self.counters.iter_mut() {
+            *counter /= 2;
+        }
+        self.additions = 0;
+    }
+
+    // key 在每一行中对应的计数器下标
+    // 通过两个哈希值的线性组合（double hashing）得到每一行的哈希值
+    fn slots<K: Hash>(&self, key: &K) -> impl Iterator<Item = usize> {
+        let hash = self.hasher.hash_one(key);
+        let (h1, h2) = (hash as u32 as u64, (hash >> 32) | 1);
+        let width = self.width as u64;
+        (0..self.depth as u64).map(move |row| {
+            (row * width + h1.wrapping_add(row.wrapping_mul(h2)) % width) as usize
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sketch_estimate() {
+        // 哈希函数的种子是随机的，width 足够大时几个 key 的计数才不会完全冲突
+        let mut sketch = FrequencySketch::new(4096, 4);
+        assert_eq!((sketch.width(), sketch.depth()), (4096, 4));
+        assert_eq!(sketch.estimate(&"a"), 0);
+
+        for _ in 0..5 {
+            sketch.increment(&"a");
+        }
+        sketch.increment(&"b");
+        // 只会高估
+        assert!(sketch.estimate(&"a") >= 5);
+        assert!(sketch.estimate(&"b") >= 1);
+        assert!(sketch.estimate(&"a") > sketch.estimate(&"b"));
+
+        sketch.age();
+        assert!(sketch.estimate(&"a") >= 2);
+        assert!(sketch.estimate(&"a") < 5);
+    }
+
+    #[test]
+    fn sketch_aging() {
+        let mut sketch = FrequencySketch::new(4, 1);
+        // 计数器不会溢出
+        for _ in 0..39 {
+            sketch.increment(&0);
+        }
+        assert_eq!(sketch.estimate(&0), 39);
+
+        // 累计 width * 10 次后自动减半
+        sketch.increment(&0);
+        assert_eq!(sketch.estimate(&0), 20);
+
+        let mut sketch = FrequencySketch::new(1000, 1);
+        for _ in 0..300 {
+            sketch.increment(&0);
+        }
+        assert_eq!(sketch.estimate(&0), MAX_COUNT);
+    }
+
+    #[test]
+    fn sketch_size_overflow() {
+        // width * depth 溢出时缩小 width
+        let sketch = FrequencySketch::new(usize::MAX, 2);
+        assert_eq!((sketch.width(), sketch.depth()), (MAX_COUNTERS / 2, 2));
+        let sketch = FrequencySketch::new(usize::MAX, usize::MAX);
+        assert_eq!((sketch.width(), sketch.depth()), (1, MAX_COUNTERS));
+        let sketch = FrequencySketch::new(0, 0);
+        assert_eq!((sketch.width(), sketch.depth()), (1, 1));
+    }
+}
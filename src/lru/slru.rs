@@ -0,0 +1,242 @@
+use core::hash::Hash;
+use core::mem;
+
+use crate::lru::arena::Index;
+use crate::lru::err::CacheError;
+use crate::lru::list::LinkedList;
+use crate::lru::HashMap;
+
+// 默认的受保护区所占容量比例（百分比）
+const DEFAULT_PROTECTED_PERCENT: usize = 80;
+
+struct SlruItem<K, V> {
+    key: K,
+    value: V,
+}
+
+// 数据所在的区域
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Segment {
+    Probationary,
+    Protected,
+}
+
+// 分段 LRU 缓存：新数据先进入试用区，在试用区中再次被访问后晋升到受保护区
+// 受保护区已满时，其中最久未访问的数据降级回试用区头部
+// 只从试用区尾部淘汰，因此只访问一次的数据（例如一次遍历）不会挤掉被反复访问的数据
+// 两个区域的容量固定，不带有超时淘汰机制
+pub struct SlruCache<K, V>
+where
+    K: Eq + Hash,
+{
+    probationary: LinkedList<SlruItem<K, V>>,
+    protected: LinkedList<SlruItem<K, V>>,
+    map: HashMap<K, (Segment, Index)>,
+}
+
+impl<K, V> SlruCache<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    // 受保护区占 cap 的 80%
+    pub fn new_with_cap(cap: usize) -> Self {
+        Self::new_with_cap_split(cap, cap * DEFAULT_PROTECTED_PERCENT / 100)
+    }
+
+    // 受保护区的容量为 protected_cap，其余为试用区
+    // cap 不为 0 时试用区至少保留 1 个位置，否则新数据无法进入缓存
+    pub fn new_with_cap_split(cap: usize, protected_cap: usize) -> Self {
+        let protected_cap = protected_cap.min(cap.saturating_sub(1));
+        SlruCache {
+            probationary: LinkedList::new_with_cap(cap - protected_cap),
+            protected: LinkedList::new_with_cap(protected_cap),
+            map: HashMap::with_capacity(cap),
+        }
+    }
+
+    // 查询 key 对应的 value
+    // 位于试用区时晋升到受保护区头部，位于受保护区时移动到受保护区头部
+    pub fn query(&mut self, key: &K) -> Result<&V, CacheError> {
+        let (segment, index) = *self.map.get(key).ok_or(CacheError::CacheMiss)?;
+        let (segment, index) = match segment {
+            Segment::Protected => self.touch_protected(&index)?,
+            Segment::Probationary => self.promote(key, &index)?,
+        };
+        let node = self.list(segment).get(&index)?;
+        Ok(&node.value.value)
+    }
+
+    // 查询 key 对应的 value，不改变数据所在的区域和访问顺序
+    pub fn peek(&self, key: &K) -> Result<&V, CacheError> {
+        let (segment, index) = self.map.get(key).ok_or(CacheError::CacheMiss)?;
+        let node = self.list(*segment).get(index)?;
+        Ok(&node.value.value)
+    }
+
+    // 插入 key-value，key 已存在时覆盖并返回旧的 value（与 query 相同，视为一次访问）
+    // 新数据进入试用区头部，试用区已满时淘汰试用区尾部的数据
+    pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>, CacheError> {
+        if self.map.contains_key(&key) {
+            self.query(&key)?;
+            let (segment, index) = *self.map.get(&key).ok_or(CacheError::CacheMiss)?;
+            let node = self.list_mut(segment).get_mut(&index)?;
+            return Ok(Some(mem::replace(&mut node.value.value, value)));
+        }
+
+        // 试用区容量为 0 时由 push_front 返回 ZeroCapacity
+        if self.probationary.is_full() && !self.probationary.is_empty() {
+            let item = self.probationary.pop_back()?;
+            self.map.remove(&item.key);
+        }
+        let index = self.probationary.push_front(SlruItem {
+            key: key.clone(),
+            value,
+        })?;
+        self.map.insert(key, (Segment::Probationary, index));
+        Ok(None)
+    }
+
+    pub fn remove(&mut self, key: &K) -> Result<V, CacheError> {
+        let (segment, index) = self.map.remove(key).ok_or(CacheError::CacheMiss)?;
+        let item = self.list_mut(segment).remove(&index)?;
+        Ok(item.value)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    // key 是否位于受保护区
+    pub fn is_protected(&self, key: &K) -> bool {
+        matches!(self.map.get(key), Some((Segment::Protected, _)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    // 两个区域的容量之和
+    pub fn capacity(&self) -> usize {
+        self.probationary.capacity() + self.protected.capacity()
+    }
+
+    fn touch_protected(&mut self, index: &Index) -> Result<(Segment, Index), CacheError> {
+        self.protected.move_to_head(index)?;
+        Ok((Segment::Protected, *index))
+    }
+
+    // 从试用区晋升到受保护区，受保护区已满时将其尾部的数据降级到试用区头部
+    // 受保护区容量为 0 时只在试用区内移动到头部
+    fn promote(&mut self, key: &K, index: &Index) -> Result<(Segment, Index), CacheError> {
+        if self.protected.capacity() == 0 {
+            self.probationary.move_to_head(index)?;
+            return Ok((Segment::Probationary, *index));
+        }
+
+        let item = self.probationary.remove(index)?;
+        if self.protected.is_full() {
+            // 试用区刚刚移出了一个数据，一定有空位
+            let demoted = self.protected.pop_back()?;
+            let demoted_key = demoted.key.clone();
+            let demoted_index = self.probationary.push_front(demoted)?;
+            self.map
+                .insert(demoted_key, (Segment::Probationary, demoted_index));
+        }
+        let index = self.protected.push_front(item)?;
+        self.map.insert(key.clone(), (Segment::Protected, index));
+        Ok((Segment::Protected, index))
+    }
+
+    fn list(&self, segment: Segment) -> &LinkedList<SlruItem<K, V>> {
+        match segment {
+            Segment::Probationary => &self.probationary,
+            Segment::Protected => &self.protected,
+        }
+    }
+
+    fn list_mut(&mut self, segment: Segment) -> &mut LinkedList<SlruItem<K, V>> {
+        match segment {
+            Segment::Probationary => &mut self.probationary,
+            Segment::Protected => &mut self.protected,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::lru::cache::Cache;
+
+    use super::*;
+
+    #[test]
+    fn slru_cache_new() {
+        let slru_cache = SlruCache::<i32, i32>::new_with_cap(10);
+        assert_eq!(slru_cache.capacity(), 10);
+        assert_eq!(slru_cache.protected.capacity(), 8);
+
+        // 试用区至少保留 1 个位置
+        let slru_cache = SlruCache::<i32, i32>::new_with_cap_split(3, 5);
+        assert_eq!(slru_cache.probationary.capacity(), 1);
+        assert_eq!(slru_cache.protected.capacity(), 2);
+        assert!(slru_cache.is_empty());
+
+        let mut slru_cache = SlruCache::<i32, i32>::new_with_cap(0);
+        assert_eq!(slru_cache.insert(0, 0), Err(CacheError::ZeroCapacity));
+    }
+
+    #[test]
+    fn slru_cache_promote_and_demote() {
+        let mut slru_cache = SlruCache::<i32, i32>::new_with_cap_split(4, 2);
+        for ele in 0..2 {
+            assert_eq!(slru_cache.insert(ele, ele), Ok(None));
+        }
+        assert!(!slru_cache.is_protected(&0));
+
+        // 试用区中再次被访问后晋升
+        assert_eq!(slru_cache.query(&0), Ok(&0));
+        assert_eq!(slru_cache.query(&1), Ok(&1));
+        assert!(slru_cache.is_protected(&0));
+        assert!(slru_cache.is_protected(&1));
+
+        // 受保护区已满，最久未访问的 0 降级回试用区
+        slru_cache.insert(2, 2).unwrap();
+        assert_eq!(slru_cache.insert(2, 20), Ok(Some(2)));
+        assert!(slru_cache.is_protected(&2));
+        assert!(!slru_cache.is_protected(&0));
+        assert_eq!(slru_cache.peek(&0), Ok(&0));
+        assert_eq!(slru_cache.len(), 3);
+
+        assert_eq!(slru_cache.remove(&2), Ok(20));
+        assert_eq!(slru_cache.remove(&2), Err(CacheError::CacheMiss));
+        assert_eq!(slru_cache.query(&2), Err(CacheError::CacheMiss));
+    }
+
+    #[test]
+    fn slru_cache_scan_resistant() {
+        let mut slru_cache = SlruCache::<i32, i32>::new_with_cap_split(4, 2);
+        let mut lru_cache = Cache::<i32, i32>::new_with_cap(4);
+        for hot in [0, 1] {
+            slru_cache.insert(hot, hot).unwrap();
+            slru_cache.query(&hot).unwrap();
+            lru_cache.insert(hot, hot).unwrap();
+            lru_cache.query(&hot).unwrap();
+        }
+
+        // 只访问一次的遍历只会在试用区中互相淘汰
+        for ele in 100..120 {
+            slru_cache.insert(ele, ele).unwrap();
+            lru_cache.insert(ele, ele).unwrap();
+        }
+        assert!(!lru_cache.contains_key(&0));
+        assert!(!lru_cache.contains_key(&1));
+        assert_eq!(slru_cache.query(&0), Ok(&0));
+        assert_eq!(slru_cache.query(&1), Ok(&1));
+        assert_eq!(slru_cache.len(), 4);
+        assert!(slru_cache.contains_key(&119));
+        assert!(!slru_cache.contains_key(&117));
+    }
+}
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Mutex, MutexGuard};
+
+use crate::lru::cache::Cache;
+use crate::lru::err::CacheError;
+
+// 通过 Mutex 在多个线程之间共享的 Cache
+pub struct SyncCache<K, V>
+where
+    K: Eq + Hash,
+{
+    inner: Mutex<Cache<K, V>>,
+}
+
+impl<K, V> SyncCache<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    pub fn new(cache: Cache<K, V>) -> Self {
+        SyncCache {
+            inner: Mutex::new(cache),
+        }
+    }
+
+    pub fn insert(&self, key: K, value: V) -> Result<Option<V>, CacheError> {
+        self.lock().insert(key, value)
+    }
+
+    // 与 Cache::query 相同，但无法在释放锁后返回引用，因此返回 value 的拷贝（要求 V: Clone）
+    pub fn query(&self, key: &K) -> Result<V, CacheError>
+    where
+        V: Clone,
+    {
+        self.lock().query(key).cloned()
+    }
+
+    pub fn remove(&self, key: &K) -> Result<V, CacheError> {
+        self.lock().remove(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.lock().contains_key(key)
+    }
+
+    // 在锁外对 key 的 value 执行 f，避免耗时的 f 阻塞其它线程
+    // 先在锁内拷贝出 value，释放锁后执行 f，再重新加锁写回（并移动到头部）
+    // 如果 f 执行期间该数据被淘汰或删除，则放弃写回并返回 CacheMiss，不会使其“复活”
+    // 如果 f 执行期间该数据被其它线程修改，写回会覆盖其它线程的修改
+    // 只有第一次读取计入命中统计，写回前的存在性检查不计入
+    pub fn query_and_update<F>(&self, key: &K, f: F) -> Result<(), CacheError>
+    where
+        V: Clone,
+        F: FnOnce(&mut V),
+    {
+        let mut value = self.lock().query(key)?.clone();
+        f(&mut value);
+
+        let mut cache = self.lock();
+        if !cache.contains_key(key) {
+            return Err(CacheError::CacheMiss);
+        }
+        cache.insert(key.clone(), value)?;
+        Ok(())
+    }
+
+    // 与 Cache::get_many_or_load 相同，但 batch_loader 在锁外执行，耗时的批量加载不会阻塞其它线程
+    // 加载期间其它线程写入的同一个 key 会被加载得到的 value 覆盖
+    pub fn get_many_or_load<F>(
+        &self,
+        keys: &[K],
+        batch_loader: F,
+    ) -> Result<Vec<Option<V>>, CacheError>
+    where
+        V: Clone,
+        F: FnOnce(&[K]) -> HashMap<K, V>,
+    {
+        let (mut values, missing) = self.lock().split_hits(keys)?;
+        if missing.is_empty() {
+            return Ok(values);
+        }
+
+        let loaded = batch_loader(&missing);
+        self.lock()
+            .fill_loaded(keys, &missing, loaded, &mut values)?;
+        Ok(values)
+    }
+
+    // 某个线程在持有锁时 panic 不会破坏缓存的内部结构，因此忽略锁的中毒状态
+    fn lock(&self) -> MutexGuard<'_, Cache<K, V>> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{mpsc, Arc};
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn sync_cache_query_and_update() {
+        let mut cache = Cache::<i32, i32>::new_with_cap(4);
+        cache.insert(1, 1).unwrap();
+        cache.insert(2, 2).unwrap();
+        let sync_cache = Arc::new(SyncCache::new(cache));
+
+        let (entered_tx, entered_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let slow = {
+            let sync_cache = Arc::clone(&sync_cache);
+            thread::spawn(move || {
+                sync_cache.query_and_update(&1, |v| {
+                    entered_tx.send(()).unwrap();
+                    release_rx.recv().unwrap();
+                    *v += 100;
+                })
+            })
+        };
+
+        // 慢更新执行期间没有持有锁，其它 key 的更新不会被阻塞
+        entered_rx.recv().unwrap();
+        assert!(sync_cache.inner.try_lock().is_ok());
+        for _ in 0..10 {
+            sync_cache.query_and_update(&2, |v| *v += 1).unwrap();
+        }
+        release_tx.send(()).unwrap();
+
+        assert_eq!(slow.join().unwrap(), Ok(()));
+        let mut cache = sync_cache.lock();
+        // 每次更新只计入一次命中
+        assert_eq!(cache.stats().hits, 11);
+        assert_eq!(cache.query(&1), Ok(&101));
+        assert_eq!(cache.query(&2), Ok(&12));
+    }
+
+    #[test]
+    fn sync_cache_get_many_or_load() {
+        let mut cache = Cache::<i32, i32>::new_with_cap(2);
+        cache.insert(1, 1).unwrap();
+        let sync_cache = SyncCache::new(cache);
+
+        let mut calls = vec![];
+        let values = sync_cache.get_many_or_load(&[1, 2, 3, 2, 4], |keys| {
+            // 加载期间没有持有锁，否则这里会死锁
+            assert_eq!(sync_cache.len(), 1);
+            calls.push(keys.to_vec());
+            keys.iter()
+                .filter(|key| **key != 4)
+                .map(|key| (*key, *key * 10))
+                .collect()
+        });
+        assert_eq!(calls, vec![vec![2, 3, 4]]);
+        // 未命中的 key 多于容量，被淘汰的 value 仍然返回
+        assert_eq!(
+            values,
+            Ok(vec![Some(1), Some(20), Some(30), Some(20), None])
+        );
+        assert_eq!(sync_cache.len(), 2);
+        assert!(sync_cache.contains_key(&3));
+    }
+
+    #[test]
+    fn sync_cache_query_and_update_evicted() {
+        let mut cache = Cache::<i32, i32>::new_with_cap(4);
+        cache.insert(1, 1).unwrap();
+        let sync_cache = SyncCache::new(cache);
+
+        // 更新期间数据被删除，放弃写回
+        let result = sync_cache.query_and_update(&1, |v| {
+            sync_cache.lock().remove(&1).unwrap();
+            *v += 1;
+        });
+        assert_eq!(result, Err(CacheError::CacheMiss));
+        assert_eq!(sync_cache.lock().len(), 0);
+        assert_eq!(
+            sync_cache.query_and_update(&1, |v| *v += 1),
+            Err(CacheError::CacheMiss)
+        );
+    }
+
+    #[test]
+    fn sync_cache_concurrent_access() {
+        fn assert_sync<T: Send + Sync>() {}
+        assert_sync::<SyncCache<String, Vec<u8>>>();
+
+        let threads = 8;
+        let per_thread = 500;
+        let sync_cache = Arc::new(SyncCache::new(Cache::<i32, i32>::new_with_cap(
+            threads * per_thread,
+        )));
+
+        let handles: Vec<_> = (0..threads as i32)
+            .map(|t| {
+                let sync_cache = Arc::clone(&sync_cache);
+                thread::spawn(move || {
+                    let base = t * per_thread as i32;
+                    for key in base..base + per_thread as i32 {
+                        assert_eq!(sync_cache.insert(key, key), Ok(None));
+                        assert_eq!(sync_cache.query(&key), Ok(key));
+                        assert!(sync_cache.contains_key(&key));
+                        assert_eq!(sync_cache.insert(key, key * 2), Ok(Some(key)));
+                        if key % 2 == 0 {
+                            assert_eq!(sync_cache.remove(&key), Ok(key * 2));
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(sync_cache.len(), threads * per_thread / 2);
+        for key in 0..(threads * per_thread) as i32 {
+            if key % 2 == 0 {
+                assert_eq!(sync_cache.query(&key), Err(CacheError::CacheMiss));
+            } else {
+                assert_eq!(sync_cache.query(&key), Ok(key * 2));
+            }
+        }
+    }
+}
@@ -1,6 +1,4 @@
-mod lru;
-
-use crate::lru::cache::Cache;
+use rust_cache::lru::cache::Cache;
 use std::time;
 use std::thread;
 
@@ -8,25 +6,25 @@ fn main() {
     let mut lru_cache = Cache::<i32, i32>::new_with_cap_timeout(5, time::Duration::from_millis(1000));
 
     // [1-1]
-    lru_cache.insert(1, 1);
+    lru_cache.insert_unwrap(1, 1);
     let v = lru_cache.query(&1).unwrap();
     println!("get 1: {v}");
     // [2-2 1-1]
-    lru_cache.insert(2, 2);
+    lru_cache.insert_unwrap(2, 2);
     let v = lru_cache.query(&2).unwrap();
     println!("get 2: {v}");
     // [3-3 2-2 1-1]
-    lru_cache.insert(3, 3);
+    lru_cache.insert_unwrap(3, 3);
     let v = lru_cache.query(&3).unwrap();
     println!("get 3: {v}");
     thread::sleep(time::Duration::from_millis(500));
 
     // [4-4 3-3 2-2 1-1]
-    lru_cache.insert(4, 4);
+    lru_cache.insert_unwrap(4, 4);
     let v = lru_cache.query(&4).unwrap();
     println!("get 4: {v}");
     // [5-5 4-4 3-3 2-2 1-1]
-    lru_cache.insert(5, 5);
+    lru_cache.insert_unwrap(5, 5);
     let v = lru_cache.query(&5).unwrap();
     println!("get 5: {v}");
 
@@ -48,7 +46,8 @@ fn main() {
     println!("current length: {len}");
 
     // [1-10, 4-4]
-    lru_cache.insert(1, 10);
+    let old = lru_cache.insert(1, 10).unwrap();
+    println!("insert 1, old value: {old:?}");
     let len = lru_cache.len();
     println!("current length: {len}");
 
@@ -0,0 +1,65 @@
+// 关闭 std 特性（cargo test --no-default-features --features alloc）时运行
+// 检查 no_std + alloc 构建下保留的数据结构可以正常使用
+#![cfg(not(feature = "std"))]
+
+use rust_cache::lru::arena::Arena;
+use rust_cache::lru::cache::Cache;
+use rust_cache::lru::err::{CacheError, ListError};
+use rust_cache::lru::lfu::LfuCache;
+use rust_cache::lru::list::LinkedList;
+use rust_cache::lru::slru::SlruCache;
+
+#[test]
+fn no_std_arena_and_list() {
+    let mut arena = Arena::new_with_cap(2);
+    let index = arena.insert(0).unwrap();
+    assert_eq!(arena.get(&index), Some(&0));
+    assert_eq!(arena.remove(&index), Some(0));
+
+    let mut list = LinkedList::new_with_cap(3);
+    let index = list.push_front(0).unwrap();
+    list.push_front(1).unwrap();
+    list.push_back(2).unwrap();
+    list.reposition_to_head(&index).unwrap();
+    assert!(list.iter().eq(&[0, 1, 2]));
+    assert_eq!(list.pop_back(), Ok(2));
+    list.clear();
+    assert_eq!(list.pop_front(), Err(ListError::ListEmpty));
+}
+
+#[test]
+fn no_std_lfu_and_slru() {
+    let mut lfu_cache = LfuCache::new_with_cap(2);
+    lfu_cache.insert(0, 0).unwrap();
+    lfu_cache.insert(1, 1).unwrap();
+    lfu_cache.query(&0).unwrap();
+    lfu_cache.insert(2, 2).unwrap();
+    assert!(!lfu_cache.contains_key(&1));
+
+    let mut slru_cache = SlruCache::new_with_cap_split(2, 1);
+    slru_cache.insert(0, 0).unwrap();
+    assert_eq!(slru_cache.query(&0), Ok(&0));
+    assert!(slru_cache.is_protected(&0));
+    assert_eq!(slru_cache.remove(&0), Ok(0));
+    assert_eq!(slru_cache.query(&0), Err(CacheError::CacheMiss));
+}
+
+#[test]
+fn no_std_cache() {
+    let mut cache = Cache::new_with_cap(2);
+    cache.insert(0, 0).unwrap();
+    cache.insert(1, 1).unwrap();
+    assert_eq!(cache.query(&0), Ok(&0));
+    cache.insert(2, 2).unwrap();
+    assert!(!cache.contains_key(&1));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.total_evicted(), 1);
+
+    let mut cache = Cache::new_with_cap(1);
+    cache.set_admission_sketch(16, 4);
+    cache.insert(0, 0).unwrap();
+    cache.query(&0).unwrap();
+    assert_eq!(cache.insert(1, 1), Err(CacheError::NotAdmitted));
+    assert_eq!(cache.get_or_insert_with(2, || 2), Ok(&2));
+    assert!(cache.iter().eq([(&0, &0)]));
+}